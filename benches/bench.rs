@@ -1,37 +1,47 @@
 use omalley_aoc2023 as aoc_lib;
+use aoc_lib::utils::{self, Parts};
 use criterion::{criterion_group, criterion_main, Criterion};
+use std::env;
 
-macro_rules! benchmarks_internal {
-  ( $($day:ident),* ) => {
-    paste::paste!{
-      $(fn [<$day _benchmark>](c: &mut Criterion) {
-          use aoc_lib::$day;
-          let input_data = aoc_lib::utils::read_inputs("input", &vec![stringify!($day)])
-            .expect("can't read input");
-          let input = $day::generator(&input_data[0]);
-          c.bench_function(concat!(stringify!($day), " gen"), |b| {
-            b.iter(|| $day::generator(&input_data[0]))
-          });
-          c.bench_function(concat!(stringify!($day), " part 1"), |b| {
-            b.iter(|| $day::part1(&input))
-          });
-          c.bench_function(concat!(stringify!($day), " part 2"), |b| {
-            b.iter(|| $day::part2(&input))
-          });
-        }
-        criterion_group!($day, [<$day _benchmark>]);
-      )*
+/// The environment variable with a comma separated list of the days to
+/// benchmark, such as `AOC_DAYS=1-5,day12` or `AOC_DAYS=all-slow`, in the
+/// same form as the runner's days. All of the days are run if it isn't set.
+const DAYS_VARIABLE: &str = "AOC_DAYS";
 
-      criterion_main!($($day),*);
-    }
-  };
-}
+/// The environment variable with the directory to read the input from.
+const INPUT_VARIABLE: &str = "AOC_INPUT";
+const DEFAULT_INPUT: &str = "input";
 
-#[macro_export]
-macro_rules! benchmarks {
-  ( $($day:literal),* ) => {
-    paste::paste!{ benchmarks_internal!{$( [<day $day>] ),*} }
+/// The days tagged slow take long enough that fewer samples are used.
+const SLOW_SAMPLE_SIZE: usize = 10;
+
+/// Benchmark the generator and both parts of each picked day. Criterion's
+/// own filter also works on the names, such as `cargo bench -- day12/part2`.
+fn benchmarks(c: &mut Criterion) {
+  let directory = env::var(INPUT_VARIABLE).unwrap_or_else(|_| DEFAULT_INPUT.to_string());
+  let terms: Vec<String> = env::var(DAYS_VARIABLE).into_iter().collect();
+  let picked = utils::pick_days(&terms, Parts::Both)
+      .unwrap_or_else(|e| panic!("Bad {DAYS_VARIABLE}: {e}"));
+  for (day, _) in aoc_lib::DAYS.iter().zip(picked).filter(|(_, p)| p.is_some()) {
+    let input_data = match utils::read_input(&directory, day.name) {
+      Ok(data) => data,
+      Err(e) => {
+        eprintln!("Skipping {}: {e}", day.name);
+        continue
+      }
+    };
+    let input = (day.parse)(&input_data)
+        .unwrap_or_else(|e| panic!("Can't parse {directory}/{}: {e}", day.name));
+    let mut group = c.benchmark_group(day.name);
+    if day.info.has_tag("slow") {
+      group.sample_size(SLOW_SAMPLE_SIZE);
+    }
+    group.bench_function("generator", |b| b.iter(|| (day.parse)(&input_data)));
+    group.bench_function("part1", |b| b.iter(|| (day.part1)(&input)));
+    group.bench_function("part2", |b| b.iter(|| (day.part2)(&input)));
+    group.finish();
   }
 }
 
-benchmarks!(14);
+criterion_group!(benches, benchmarks);
+criterion_main!(benches);
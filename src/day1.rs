@@ -1,5 +1,7 @@
-pub fn generator(input: &str) -> Vec<String> {
-  input.lines().map(|l| l.to_string()).collect()
+use crate::utils::{DayInfo, Example, ParseError};
+
+pub fn generator(input: &str) -> Result<Vec<String>, ParseError> {
+  Ok(input.lines().map(|l| l.to_string()).collect())
 }
 
 /// Add each line as first and last digit
@@ -15,19 +17,18 @@ pub fn part1(input: &[String]) -> i32 {
 /// Does the given string start with a digit (numeric or name)? If so, return its value.
 fn digit(str: &str) -> Option<i32> {
   match str.chars().next() {
-    Some('o') => if str.starts_with("one") { return Some(1) },
-    Some('t') => if str.starts_with("two") { return Some(2) }
-      else if str.starts_with("three") { return Some(3) },
-    Some('f') => if str.starts_with("four") { return Some(4) }
-      else if str.starts_with("five") { return Some(5) },
-    Some('s') => if str.starts_with("six") { return Some(6) }
-      else if str.starts_with("seven") { return Some(7) },
-    Some('e') => if str.starts_with("eight") { return Some(8) },
-    Some('n') => if str.starts_with("nine") { return Some(9) },
-    Some(ch) => if ch.is_ascii_digit() { return Some(ch as i32 - '0' as i32)},
-    _ => {},
+    Some('o') if str.starts_with("one") => Some(1),
+    Some('t') if str.starts_with("two") => Some(2),
+    Some('t') if str.starts_with("three") => Some(3),
+    Some('f') if str.starts_with("four") => Some(4),
+    Some('f') if str.starts_with("five") => Some(5),
+    Some('s') if str.starts_with("six") => Some(6),
+    Some('s') if str.starts_with("seven") => Some(7),
+    Some('e') if str.starts_with("eight") => Some(8),
+    Some('n') if str.starts_with("nine") => Some(9),
+    Some(ch) if ch.is_ascii_digit() => Some(ch as i32 - '0' as i32),
+    _ => None,
   }
-  None
 }
 
 /// Find the first or last digit in the string, depending on the passed in iterator.
@@ -48,22 +49,13 @@ pub fn part2(input: &[String]) -> i32 {
     .sum()
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day1::{generator, part1, part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "1abc2
 pqr3stu8vwx
 a1b2c3d4e5f
 treb7uchet";
 
-  #[test]
-  fn test_part1() {
-    assert_eq!(142, part1(&generator(INPUT)));
-  }
-
-  const INPUT2: &str =
+const EXAMPLE2: &str =
 "two1nine
 eightwothree
 abcone2threexyz
@@ -72,10 +64,26 @@ xtwone3four
 zoneight234
 7pqrstsixteen";
 
+pub const INFO: DayInfo = DayInfo{title: "Trebuchet?!", year: 2023, day: 1, examples: &[
+  Example{input: EXAMPLE, part1: Some("142"), part2: None},
+  Example{input: EXAMPLE2, part1: None, part2: Some("281")}],
+  tags: &[]};
+
+crate::solution!(Vec<String>);
+
+#[cfg(test)]
+mod tests {
+  use crate::day1::{generator, part1, part2, EXAMPLE, EXAMPLE2};
+
+  #[test]
+  fn test_part1() {
+    assert_eq!(142, part1(&generator(EXAMPLE).unwrap()));
+  }
+
   #[test]
   fn test_part2() {
-    assert_eq!(142, part2(&generator(INPUT)));
-    assert_eq!(281, part2(&generator(INPUT2)));
-    assert_eq!(21, part2(&generator("twone")));
+    assert_eq!(142, part2(&generator(EXAMPLE).unwrap()));
+    assert_eq!(281, part2(&generator(EXAMPLE2).unwrap()));
+    assert_eq!(21, part2(&generator("twone").unwrap()));
   }
 }
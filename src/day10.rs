@@ -1,9 +1,5 @@
 use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, Stroke, Transform};
-
-#[derive(Clone,Copy,Debug,PartialEq)]
-pub enum Direction {
-  North, East, South, West,
-}
+use crate::utils::{DayInfo, Dir4, Example, Grid, ParseError, Point};
 
 #[derive(Clone,Copy,Debug,PartialEq)]
 pub enum PipeSection {
@@ -24,59 +20,63 @@ impl PipeSection {
     }
   }
 
-  fn has_direction(&self, dir: Direction) -> bool {
+  fn has_direction(&self, dir: Dir4) -> bool {
     self.has_connections()[dir as usize]
   }
 
-  fn twist(&self, facing: Direction) -> Option<Direction> {
+  fn twist(&self, facing: Dir4) -> Option<Dir4> {
     match self {
       PipeSection::NorthSouth => {
         match facing {
-          Direction::North => Some(Direction::North),
-          Direction::South => Some(Direction::South),
+          Dir4::North => Some(Dir4::North),
+          Dir4::South => Some(Dir4::South),
           _ => None,
         }
       }
       PipeSection::EastWest => {
         match facing {
-          Direction::East => Some(Direction::East),
-          Direction::West => Some(Direction::West),
+          Dir4::East => Some(Dir4::East),
+          Dir4::West => Some(Dir4::West),
           _ => None,
         }
       }
       PipeSection::NorthEast => {
         match facing {
-          Direction::South => Some(Direction::East),
-          Direction::West => Some(Direction::North),
+          Dir4::South => Some(Dir4::East),
+          Dir4::West => Some(Dir4::North),
           _ => None,
         }
       }
       PipeSection::NorthWest => {
         match facing {
-          Direction::South => Some(Direction::West),
-          Direction::East => Some(Direction::North),
+          Dir4::South => Some(Dir4::West),
+          Dir4::East => Some(Dir4::North),
           _ => None,
         }
       }
       PipeSection::SouthWest => {
         match facing {
-          Direction::North => Some(Direction::West),
-          Direction::East => Some(Direction::South),
+          Dir4::North => Some(Dir4::West),
+          Dir4::East => Some(Dir4::South),
           _ => None,
         }
       }
       PipeSection::SouthEast => {
         match facing {
-          Direction::North => Some(Direction::East),
-          Direction::West => Some(Direction::South),
+          Dir4::North => Some(Dir4::East),
+          Dir4::West => Some(Dir4::South),
           _ => None,
         }
       }
       _ => None,
     }
   }
+}
+
+impl TryFrom<char> for PipeSection {
+  type Error = String;
 
-  fn from_char(ch: char) -> Result<Self, String> {
+  fn try_from(ch: char) -> Result<Self, String> {
     match ch {
       '|' => Ok(PipeSection::NorthSouth),
       '-' => Ok(PipeSection::EastWest),
@@ -91,98 +91,48 @@ impl PipeSection {
   }
 }
 
-#[derive(Clone,Copy,Debug,Default,PartialEq)]
-pub struct Point {
-  x: i64,
-  y: i64,
-}
-
-impl Point {
-  fn step(&self, direction: Direction) -> Point {
-    match direction {
-      Direction::North => Point{x: self.x, y: self.y - 1},
-      Direction::South => Point{x: self.x, y: self.y + 1},
-      Direction::East => Point{x: self.x + 1, y: self.y},
-      Direction::West => Point{x: self.x - 1, y: self.y},
-    }
-  }
-}
-
 #[derive(Clone,Debug)]
 struct Walker {
-  location: Point,
-  facing: Direction,
+  location: Point<i64>,
+  facing: Dir4,
 }
 
 #[derive(Clone,Debug)]
 pub struct Map {
-  start: Point,
-  size: Point,
-  grid: Vec<Vec<PipeSection>>,
+  start: Point<i64>,
+  grid: Grid<PipeSection>,
 }
 
 impl Map {
-  fn find_start(grid: &[Vec<PipeSection>]) -> Result<Point, String> {
-    for (y, row) in grid.iter().enumerate() {
-      for (x, loc) in row.iter().enumerate() {
-        if *loc == PipeSection::Start {
-          return Ok(Point{x: x as i64, y: y as i64})
-        }
-      }
-    }
-    Err("Start location not found!".to_string())
-  }
-
-  fn from_str(input: &str) -> Result<Self, String> {
-    let grid = input.lines().enumerate()
-        .map(| (y, l) | l.chars().enumerate()
-            .map(| (x, ch) | PipeSection::from_char(ch)
-                .map_err(|err| format!("{err} at line {} char {}", y + 1, x + 1)))
-            .collect())
-        .collect::<Result<Vec<Vec<PipeSection>>, String>>()?;
-    let size = Point{x: grid[0].len() as i64, y: grid.len() as i64};
-    let start = Self::find_start(&grid)?;
-    Ok(Map{start, grid, size})
+  fn from_str(input: &str) -> Result<Self, ParseError> {
+    let grid = Grid::parse(input)?;
+    let start = grid.position(|&loc| loc == PipeSection::Start)
+        .map(|(x, y)| Point::new(x as i64, y as i64))
+        .ok_or_else(|| ParseError::new(input, "Start location not found!"))?;
+    Ok(Map{start, grid})
   }
 
-  fn get_contents(&self, loc: Point) -> Option<PipeSection> {
-    if (0..self.size.y).contains(&loc.y) && (0..self.size.x).contains(&loc.x) {
-      Some(self.grid[loc.y as usize][loc.x as usize])
-    } else {
-      None
-    }
+  fn get_contents(&self, loc: Point<i64>) -> Option<PipeSection> {
+    self.grid.get(loc.x, loc.y).copied()
   }
 
   fn get_start_walkers(&self) -> Vec<Walker> {
-    let mut result = Vec::new();
-    if self.get_contents(self.start.step(Direction::North))
-        .map(|cont| cont.has_direction(Direction::South)) == Some(true) {
-      result.push(Walker{location: self.start, facing: Direction::North});
-    }
-    if self.get_contents(self.start.step(Direction::East))
-        .map(|cont| cont.has_direction(Direction::West)) == Some(true) {
-      result.push(Walker{location: self.start, facing: Direction::East});
-    }
-    if self.get_contents(self.start.step(Direction::South))
-        .map(|cont| cont.has_direction(Direction::North)) == Some(true) {
-      result.push(Walker{location: self.start, facing: Direction::South})
-    }
-    if self.get_contents(self.start.step(Direction::West))
-        .map(|cont| cont.has_direction(Direction::East)) == Some(true){
-      result.push(Walker{location: self.start, facing: Direction::West})
-    }
-    result
+    Dir4::ALL.into_iter()
+        .filter(|&dir| self.get_contents(self.start.step(dir, 1))
+            .is_some_and(|cont| cont.has_direction(dir.reverse())))
+        .map(|dir| Walker{location: self.start, facing: dir})
+        .collect()
   }
 
   fn step(&self, walker: &mut Walker) {
-    walker.location = walker.location.step(walker.facing);
+    walker.location = walker.location.step(walker.facing, 1);
     walker.facing = self.get_contents(walker.location).unwrap().twist(walker.facing)
-        .unwrap_or(Direction::North);
+        .unwrap_or(Dir4::North);
   }
 }
 
-pub fn generator(input: &str) -> Map {
-  Map::from_str(input).unwrap() // panics on error
+pub fn generator(input: &str) -> Result<Map, ParseError> {
+  Map::from_str(input)
 }
 
 pub fn part1(input: &Map) -> usize {
@@ -205,8 +155,8 @@ fn translate_coord(val: i64) -> f32 {
 }
 
 pub fn save_shape(input: &Map, filename: &str) {
-  let mut pixmap = Pixmap::new(input.size.x as u32 * BOX_WIDTH,
-                               input.size.y as u32 * BOX_WIDTH).unwrap();
+  let mut pixmap = Pixmap::new(input.grid.width() as u32 * BOX_WIDTH,
+                               input.grid.height() as u32 * BOX_WIDTH).unwrap();
   let mut path_builder = PathBuilder::new();
   let mut walkers = input.get_start_walkers();
   path_builder.move_to(translate_coord(input.start.x), translate_coord(input.start.y));
@@ -230,24 +180,23 @@ pub fn save_shape(input: &Map, filename: &str) {
 
 pub fn part2(input: &Map) -> usize {
   let mut walkers = input.get_start_walkers();
-  let start_has_north = walkers.iter().any(|w| w.facing == Direction::North);
-  let mut pipe_loop =
-      vec![vec![false; input.size.x as usize]; input.size.y as usize];
-  pipe_loop[input.start.y as usize][input.start.x as usize] = true;
+  let start_has_north = walkers.iter().any(|w| w.facing == Dir4::North);
+  let mut pipe_loop = Grid::filled(input.grid.width(), input.grid.height(), false);
+  pipe_loop[(input.start.x as usize, input.start.y as usize)] = true;
   while walkers[0].location == input.start || walkers[0].location != walkers[1].location {
     for w in walkers.iter_mut() {
       input.step(w);
-      pipe_loop[w.location.y as usize][w.location.x as usize] = true;
+      pipe_loop[(w.location.x as usize, w.location.y as usize)] = true;
     }
   }
   let mut inside_count: usize = 0;
-  for (y, row) in input.grid.iter().enumerate() {
+  for (y, row) in input.grid.rows().enumerate() {
     let mut wall_count = 0;
     for (x, loc) in row.iter().enumerate() {
-      if pipe_loop[y][x] {
+      if pipe_loop[(x, y)] {
         match loc {
           PipeSection::Start => if start_has_north { wall_count += 1 },
-          other => if other.has_direction(Direction::North) { wall_count += 1},
+          other => if other.has_direction(Dir4::North) { wall_count += 1},
         }
       } else if wall_count % 2 == 1 {
         inside_count += 1;
@@ -257,15 +206,33 @@ pub fn part2(input: &Map) -> usize {
   inside_count
 }
 
+const EXAMPLE: &str = "-L|F7\n\
+                      7S-7|\n\
+                      L|7||\n\
+                      -L-J|\n\
+                      L|-JF";
+
+const EXAMPLE2: &str =
+"...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........";
+
+pub const INFO: DayInfo = DayInfo{title: "Pipe Maze", year: 2023, day: 10, examples: &[
+  Example{input: EXAMPLE, part1: Some("4"), part2: None},
+  Example{input: EXAMPLE2, part1: None, part2: Some("4")}],
+  tags: &["grid"]};
+
+crate::solution!(Map);
+
 #[cfg(test)]
 mod tests {
-  use crate::day10::{generator, part1, part2};
-
-  const INPUT: &str = "-L|F7\n\
-                       7S-7|\n\
-                       L|7||\n\
-                       -L-J|\n\
-                       L|-JF";
+  use crate::day10::{generator, part1, part2, EXAMPLE, EXAMPLE2};
 
   const INPUT2: &str = "7-F7-\n\
                         .FJ|7\n\
@@ -275,21 +242,10 @@ mod tests {
 
   #[test]
   fn test_part1() {
-    assert_eq!(4, part1(&generator(INPUT)));
-    assert_eq!(8, part1(&generator(INPUT2)));
+    assert_eq!(4, part1(&generator(EXAMPLE).unwrap()));
+    assert_eq!(8, part1(&generator(INPUT2).unwrap()));
   }
 
-  const INPUT3: &str =
-"...........
-.S-------7.
-.|F-----7|.
-.||.....||.
-.||.....||.
-.|L-7.F-J|.
-.|..|.|..|.
-.L--J.L--J.
-...........";
-
   const INPUT4: &str =
 ".F----7F7F7F7F-7....
 .|F--7||||||||FJ....
@@ -316,8 +272,8 @@ L7JLJL-JLJLJL--JLJ.L";
 
   #[test]
   fn test_part2() {
-    assert_eq!(4, part2(&generator(INPUT3)));
-    assert_eq!(8, part2(&generator(INPUT4)));
-    assert_eq!(10, part2(&generator(INPUT5)));
+    assert_eq!(4, part2(&generator(EXAMPLE2).unwrap()));
+    assert_eq!(8, part2(&generator(INPUT4).unwrap()));
+    assert_eq!(10, part2(&generator(INPUT5).unwrap()));
   }
 }
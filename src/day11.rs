@@ -1,3 +1,5 @@
+use crate::utils::{DayInfo, Example, ParseError};
+
 #[derive(Clone,Debug)]
 pub struct Galaxy {
   x: usize,
@@ -65,8 +67,8 @@ impl Map {
   }
 }
 
-pub fn generator(input: &str) -> Map {
-  Map::from_str(input)
+pub fn generator(input: &str) -> Result<Map, ParseError> {
+  Ok(Map::from_str(input))
 }
 
 pub fn part1(input: &Map) -> usize {
@@ -81,11 +83,7 @@ pub fn part2(input: &Map) -> usize {
   expanding_galaxy.sum_distances()
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day11::{generator, part1};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "...#......
 .......#..
 #.........
@@ -97,14 +95,24 @@ mod tests {
 .......#..
 #...#.....";
 
+pub const INFO: DayInfo = DayInfo{title: "Cosmic Expansion", year: 2023, day: 11, examples: &[
+  Example{input: EXAMPLE, part1: Some("374"), part2: None}],
+  tags: &["grid"]};
+
+crate::solution!(Map);
+
+#[cfg(test)]
+mod tests {
+  use crate::day11::{generator, part1, EXAMPLE};
+
   #[test]
   fn test_part1() {
-    assert_eq!(374, part1(&generator(INPUT)));
+    assert_eq!(374, part1(&generator(EXAMPLE).unwrap()));
   }
 
   #[test]
   fn test_part2() {
-    let mut input = generator(INPUT);
+    let mut input = generator(EXAMPLE).unwrap();
     input.expand(100);
     assert_eq!(8410, input.sum_distances());
   }
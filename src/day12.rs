@@ -1,3 +1,5 @@
+use crate::utils::{DayInfo, Example, ParseError, parse_chars};
+
 #[derive(Clone,Debug,PartialEq)]
 pub enum SpringState {
   Good,
@@ -70,14 +72,13 @@ pub struct Record {
 }
 
 impl Record {
-  fn from_str(input: &str) -> Result<Self, String> {
-    let (spring_str, count_str) =
-        input.split_once(' ').ok_or("Can't find separator")?;
-    let springs = spring_str.chars().map(SpringState::from_char)
-        .collect::<Result<Vec<SpringState>,String>>()?;
+  fn from_str(input: &str) -> Result<Self, ParseError> {
+    let (spring_str, count_str) = input.split_once(' ')
+        .ok_or_else(|| ParseError::new(input, "Can't find separator"))?;
+    let springs = parse_chars(spring_str, SpringState::from_char)?;
     let broken_counts = count_str.split(',')
-        .map(|x| x.parse::<usize>().map_err(|_| format!("Can't parse integer: {x}")))
-        .collect::<Result<Vec<usize>,String>>()?;
+        .map(|x| x.parse::<usize>().map_err(|_| ParseError::new(x, "Can't parse integer")))
+        .collect::<Result<Vec<usize>,ParseError>>()?;
     Ok(Record{springs, broken_counts})
   }
 
@@ -207,9 +208,8 @@ impl Record {
   }
 }
 
-pub fn generator(input: &str) -> Vec<Record> {
-  input.lines().map(Record::from_str).collect::<Result<Vec<Record>,String>>()
-      .unwrap() // panic on error
+pub fn generator(input: &str) -> Result<Vec<Record>, ParseError> {
+  input.lines().map(Record::from_str).collect()
 }
 
 pub fn part1(input: &[Record]) -> usize {
@@ -222,11 +222,7 @@ pub fn part2(input: &[Record]) -> usize {
       .sum()
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day12::{generator, part1, part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "???.### 1,1,3
 .??..??...?##. 1,1,3
 ?#?#?#?#?#?#?#? 1,3,1,6
@@ -234,22 +230,32 @@ mod tests {
 ????.######..#####. 1,6,5
 ?###???????? 3,2,1";
 
+pub const INFO: DayInfo = DayInfo{title: "Hot Springs", year: 2023, day: 12, examples: &[
+  Example{input: EXAMPLE, part1: Some("21"), part2: Some("525152")}],
+  tags: &["slow"]};
+
+crate::solution!(Vec<Record>);
+
+#[cfg(test)]
+mod tests {
+  use crate::day12::{generator, part1, part2, EXAMPLE};
+
   #[test]
   fn test_part1() {
-    assert_eq!(21, part1(&generator(INPUT)));
-    assert_eq!(15, part1(&generator("?#?????.??????. 4,1")));
+    assert_eq!(21, part1(&generator(EXAMPLE).unwrap()));
+    assert_eq!(15, part1(&generator("?#?????.??????. 4,1").unwrap()));
   }
 
   #[test]
   fn test_part2() {
-    assert_eq!(525152, part2(&generator(INPUT)));
+    assert_eq!(525152, part2(&generator(EXAMPLE).unwrap()));
   }
 
   #[test]
   fn extra_test() {
-    assert_eq!(2, part1(&generator("????? 4")));
-    assert_eq!(36, part1(&generator("??????????????? 3,4")));
-    assert_eq!(84, part1(&generator("???????????????????? 3,4,5")));
-    assert_eq!(35, part1(&generator("???????????????????? 3,4,5,2")));
+    assert_eq!(2, part1(&generator("????? 4").unwrap()));
+    assert_eq!(36, part1(&generator("??????????????? 3,4").unwrap()));
+    assert_eq!(84, part1(&generator("???????????????????? 3,4,5").unwrap()));
+    assert_eq!(35, part1(&generator("???????????????????? 3,4,5,2").unwrap()));
   }
 }
@@ -1,11 +1,15 @@
+use crate::utils::{DayInfo, Example, Grid, ParseError, blocks};
+
 #[derive(Clone,Copy,Debug,PartialEq)]
 pub enum Location {
   Ash,
   Rock,
 }
 
-impl Location {
-  fn from_char(ch: char) -> Result<Self, String> {
+impl TryFrom<char> for Location {
+  type Error = String;
+
+  fn try_from(ch: char) -> Result<Self, String> {
     match ch {
       '.' => Ok(Location::Ash),
       '#' => Ok(Location::Rock),
@@ -14,42 +18,32 @@ impl Location {
   }
 }
 
+/// The pattern, along with its transpose so that the vertical reflections can
+/// be found the same way as the horizontal ones.
 #[derive(Clone,Debug)]
 pub struct Map {
-  locations: Vec<Vec<Location>>,
-  width: usize,
-  height: usize,
+  rows: Grid<Location>,
+  columns: Grid<Location>,
 }
 
 impl Map {
-  fn from_str(input: &str) -> Result<Self, String> {
-    let locations = input.lines()
-        .map(|l| l.chars().map(Location::from_char)
-            .collect::<Result<Vec<Location>,String>>())
-        .collect::<Result<Vec<Vec<Location>>,String>>()?;
-    let width = locations[0].len();
-    let height = locations.len();
-    Ok(Map{locations, width, height})
+  fn from_str(input: &str) -> Result<Self, ParseError> {
+    let rows = Grid::parse(input)?;
+    let columns = rows.transpose();
+    Ok(Map{rows, columns})
   }
 
-  fn match_rows(&self, y1: usize, y2: usize, get: impl Fn(usize, usize) -> Location,
-                width: usize) -> usize {
-    let mut smudges = 0;
-    for x in 0..width {
-      if get(x, y1) != get(x, y2) {
-        smudges += 1;
-      }
-    }
-    smudges
+  /// Count the differences between two rows.
+  fn match_rows(grid: &Grid<Location>, y1: usize, y2: usize) -> usize {
+    grid.row(y1).iter().zip(grid.row(y2)).filter(|(a, b)| a != b).count()
   }
 
-  fn reflection_at(&self, y: usize, smudges: usize, get: impl Fn(usize, usize) -> Location,
-                   height: usize, width:  usize) -> bool {
+  fn reflection_at(grid: &Grid<Location>, y: usize, smudges: usize) -> bool {
     let mut lower = y;
     let mut upper = y + 1;
     let mut smudge_count = 0;
-    while upper < height {
-      smudge_count += self.match_rows(lower, upper, &get, width);
+    while upper < grid.height() {
+      smudge_count += Self::match_rows(grid, lower, upper);
       if lower == 0 || smudge_count > smudges {
         break;
       }
@@ -59,34 +53,22 @@ impl Map {
     smudges == smudge_count
   }
 
-  fn locate_reflection(&self, smudges: usize, get: impl Fn(usize, usize) -> Location,
-                       height: usize, width: usize) -> Option<usize> {
-    for y in 0..height - 1 {
-      if self.reflection_at(y, smudges, &get, height, width) {
-        return Some(y + 1);
-      }
-    }
-    None
+  fn locate_reflection(grid: &Grid<Location>, smudges: usize) -> Option<usize> {
+    (0..grid.height() - 1)
+        .find(|&y| Self::reflection_at(grid, y, smudges))
+        .map(|y| y + 1)
   }
 
   fn find_reflection(&self, smudges: usize) -> usize {
-    if let Some(ans) = self.locate_reflection(smudges,
-                                              |x, y| self.locations[y][x],
-                                               self.height, self.width) {
+    if let Some(ans) = Self::locate_reflection(&self.rows, smudges) {
       return ans * 100
     }
-    if let Some(ans) = self.locate_reflection(smudges,
-                                              |x, y| self.locations[x][y],
-                                              self.width, self.height) {
-      return ans
-    }
-    0
+    Self::locate_reflection(&self.columns, smudges).unwrap_or(0)
   }
 }
 
-pub fn generator(input: &str) -> Vec<Map> {
-  input.split("\n\n").map(Map::from_str).collect::<Result<Vec<Map>,String>>()
-      .unwrap() // panic on error
+pub fn generator(input: &str) -> Result<Vec<Map>, ParseError> {
+  blocks(input).map(Map::from_str).collect()
 }
 
 pub fn part1(input: &[Map]) -> usize {
@@ -97,11 +79,7 @@ pub fn part2(input: &[Map]) -> usize {
   input.iter().map(|m| m.find_reflection(1)).sum()
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day13::{generator, part1, part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "#.##..##.
 ..#.##.#.
 ##......#
@@ -118,13 +96,23 @@ mod tests {
 ..##..###
 #....#..#";
 
+pub const INFO: DayInfo = DayInfo{title: "Point of Incidence", year: 2023, day: 13, examples: &[
+  Example{input: EXAMPLE, part1: Some("405"), part2: Some("400")}],
+  tags: &["grid"]};
+
+crate::solution!(Vec<Map>);
+
+#[cfg(test)]
+mod tests {
+  use crate::day13::{generator, part1, part2, EXAMPLE};
+
   #[test]
   fn test_part1() {
-    assert_eq!(405, part1(&generator(INPUT)));
+    assert_eq!(405, part1(&generator(EXAMPLE).unwrap()));
   }
 
   #[test]
   fn test_part2() {
-    assert_eq!(400, part2(&generator(INPUT)));
+    assert_eq!(400, part2(&generator(EXAMPLE).unwrap()));
   }
 }
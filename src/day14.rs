@@ -1,128 +1,75 @@
-use std::collections::HashMap;
+use crate::utils::{DayInfo, Example, Grid, ParseError};
+use crate::utils::cycle::{self, Memory};
 
 #[derive(Clone,Copy,Debug,Eq,Hash,PartialEq)]
-pub enum RockKind {
+pub enum Spot {
+  Empty,
   RoundRock,
   CubeRock,
 }
 
-#[derive(Clone,Debug,Eq,Hash,PartialEq)]
-pub struct Rock {
-  kind: RockKind,
-  x: usize,
-  y: usize,
-}
+impl TryFrom<char> for Spot {
+  type Error = String;
 
-impl Rock {
-  fn from_char(ch: char, x: usize, y: usize) -> Result<Option<Self>, String> {
+  fn try_from(ch: char) -> Result<Self, String> {
     match ch {
-      'O' => Ok(Some(Rock{kind: RockKind::RoundRock, x, y})),
-      '#' => Ok(Some(Rock{kind: RockKind::CubeRock, x, y})),
-      '.' => Ok(None),
-      _ => Err(format!("Unknown character: {ch} at {x}, {y}")),
+      'O' => Ok(Spot::RoundRock),
+      '#' => Ok(Spot::CubeRock),
+      '.' => Ok(Spot::Empty),
+      _ => Err(format!("Unknown character: {ch}")),
     }
   }
 }
 
 #[derive(Clone,Debug,Hash,Eq,PartialEq)]
 pub struct Map {
-  columns: Vec<Vec<Rock>>,
-  rows: Vec<Vec<Rock>>,
-  width: usize,
-  height: usize,
+  grid: Grid<Spot>,
 }
 
 impl Map {
-  fn from_str(input: &str) -> Result<Self, String> {
-    let mut rows = Vec::new();
-    let mut width = 0;
-    for (y, row) in input.lines().enumerate() {
-      let mut next_row = Vec::new();
-      for (x, ch) in row.chars().enumerate() {
-        width = width.max(x + 1);
-        if let Some(rock) = Rock::from_char(ch, x, y)? {
-          next_row.push(rock);
-        }
-      }
-      rows.push(next_row);
-    }
-    let height = rows.len();
-    let columns = vec![Vec::new(); width];
-    Ok(Map{columns, width, height, rows})
+  fn from_str(input: &str) -> Result<Self, ParseError> {
+    Ok(Map{grid: Grid::parse(input)?})
   }
 
-  /// rotate the rocks into the columns
-  fn rotate_to_columns(&mut self) {
-    for row in self.rows.iter_mut() {
-      while let Some(rock) = row.pop() {
-        self.columns[rock.x].push(rock);
-      }
-    }
-  }
-
-  fn fall_north(&mut self) {
-    self.rotate_to_columns();
-    for col in self.columns.iter_mut() {
+  /// Roll the round rocks along each line toward the start of the line until
+  /// they hit a cube rock or another round rock. The location function maps
+  /// the line and the position along it to the grid.
+  fn roll(&mut self, lines: usize, length: usize,
+          location: impl Fn(usize, usize) -> (usize, usize)) {
+    for line in 0..lines {
       let mut next = 0;
-      for rock in col.iter_mut() {
-        match rock.kind {
-          RockKind::RoundRock => rock.y = next,
-          RockKind::CubeRock => next = rock.y,
+      for pos in 0..length {
+        match self.grid[location(line, pos)] {
+          Spot::CubeRock => next = pos + 1,
+          Spot::RoundRock => {
+            self.grid[location(line, pos)] = Spot::Empty;
+            self.grid[location(line, next)] = Spot::RoundRock;
+            next += 1;
+          }
+          Spot::Empty => {}
         }
-        next += 1;
       }
     }
   }
 
-  /// rotate the rocks into the rows
-  fn rotate_to_rows(&mut self) {
-   for col in self.columns.iter_mut() {
-      while let Some(rock) = col.pop() {
-        self.rows[rock.y].push(rock);
-      }
-    }
+  fn fall_north(&mut self) {
+    let (width, height) = (self.grid.width(), self.grid.height());
+    self.roll(width, height, |x, y| (x, y));
   }
 
   fn fall_west(&mut self) {
-    self.rotate_to_rows();
-    for row in self.rows.iter_mut() {
-      let mut next = 0;
-      for rock in row.iter_mut() {
-        match rock.kind {
-          RockKind::RoundRock => rock.x = next,
-          RockKind::CubeRock => next = rock.x,
-        }
-        next += 1;
-      }
-    }
+    let (width, height) = (self.grid.width(), self.grid.height());
+    self.roll(height, width, |y, x| (x, y));
   }
 
   fn fall_south(&mut self) {
-    self.rotate_to_columns();
-    for col in self.columns.iter_mut() {
-      let mut prev = self.height;
-      for rock in col.iter_mut().rev() {
-        prev -= 1;
-        match rock.kind {
-          RockKind::RoundRock => rock.y = prev,
-          RockKind::CubeRock => prev = rock.y,
-        }
-      }
-    }
+    let (width, height) = (self.grid.width(), self.grid.height());
+    self.roll(width, height, |x, y| (x, height - 1 - y));
   }
 
   fn fall_east(&mut self) {
-    self.rotate_to_rows();
-    for row in self.rows.iter_mut() {
-      let mut prev = self.width;
-      for rock in row.iter_mut().rev() {
-        prev -= 1;
-        match rock.kind {
-          RockKind::RoundRock => rock.x = prev,
-          RockKind::CubeRock => prev = rock.x,
-        }
-      }
-    }
+    let (width, height) = (self.grid.width(), self.grid.height());
+    self.roll(height, width, |y, x| (width - 1 - x, y));
   }
 
   fn cycle(&mut self) {
@@ -133,28 +80,15 @@ impl Map {
   }
 
   fn get_weight(&self) -> usize {
-    let mut result = 0;
-    for col in &self.columns {
-      for rock in col {
-        if rock.kind == RockKind::RoundRock {
-          result += self.height - rock.y;
-        }
-      }
-    }
-    for row in &self.rows {
-      for rock in row {
-        if rock.kind == RockKind::RoundRock {
-          result += self.height - rock.y;
-        }
-      }
-    }
-    result
+    self.grid.iter()
+        .filter(|(_, &spot)| spot == Spot::RoundRock)
+        .map(|((_, y), _)| self.grid.height() - y)
+        .sum()
   }
 }
 
-pub fn generator(input: &str) -> Map {
+pub fn generator(input: &str) -> Result<Map, ParseError> {
   Map::from_str(input)
-      .unwrap() // panic on error
 }
 
 pub fn part1(input: &Map) -> usize {
@@ -166,32 +100,10 @@ pub fn part1(input: &Map) -> usize {
 const PART2_REPETITIONS: usize = 1_000_000_000;
 
 pub fn part2(input: &Map) -> usize {
-  let mut work = input.clone();
-  let mut cache: HashMap<Map, usize> = HashMap::new();
-  let mut found_cycle: Option<(usize, usize)> = None;
-  for cycle in 0..PART2_REPETITIONS {
-    work.cycle();
-    if let Some(prev) = cache.insert(work.clone(), cycle + 1) {
-      found_cycle = Some((prev, cycle + 1 - prev));
-      break;
-    }
-  }
-  cache.clear();
-  // If we found a loop, simulate the remaining cycles.
-  if let Some((base, length)) = found_cycle {
-    let remaining = (PART2_REPETITIONS - base) % length;
-    for _ in 0..remaining {
-      work.cycle();
-    }
-  }
-  work.get_weight()
+  cycle::nth(input.clone(), Map::cycle, PART2_REPETITIONS, Memory::States).get_weight()
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day14::{generator, part1, part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "O....#....
 O.OO#....#
 .....##...
@@ -203,9 +115,19 @@ O.#..O.#.#
 #....###..
 #OO..#....";
 
+pub const INFO: DayInfo = DayInfo{title: "Parabolic Reflector Dish", year: 2023, day: 14, examples: &[
+  Example{input: EXAMPLE, part1: Some("136"), part2: Some("64")}],
+  tags: &["grid"]};
+
+crate::solution!(Map);
+
+#[cfg(test)]
+mod tests {
+  use crate::day14::{generator, part1, part2, EXAMPLE};
+
   #[test]
   fn test_part1() {
-    assert_eq!(136, part1(&generator(INPUT)));
+    assert_eq!(136, part1(&generator(EXAMPLE).unwrap()));
   }
 
   const THREE_CYCLE_OUTPUT: &str =
@@ -222,8 +144,8 @@ O.#..O.#.#
 
   #[test]
   fn test_3_cycles() {
-    let expected = generator(&THREE_CYCLE_OUTPUT);
-    let mut input = generator(&INPUT);
+    let expected = generator(THREE_CYCLE_OUTPUT).unwrap();
+    let mut input = generator(EXAMPLE).unwrap();
     for _ in 0..3 {
       input.cycle();
     }
@@ -232,6 +154,6 @@ O.#..O.#.#
 
   #[test]
   fn test_part2() {
-    assert_eq!(64, part2(&generator(INPUT)));
+    assert_eq!(64, part2(&generator(EXAMPLE).unwrap()));
   }
 }
@@ -1,9 +1,11 @@
+use crate::utils::{DayInfo, Example, ParseError};
+
 fn hash(word: &str) -> usize {
   word.chars().fold(0, |acc, ch| ((acc + ch as usize) * 17) % 256)
 }
 
-pub fn generator(input: &str) -> Vec<String> {
-  input.split(',').map(|s| s.trim().to_string()).collect()
+pub fn generator(input: &str) -> Result<Vec<String>, ParseError> {
+  Ok(input.split(',').map(|s| s.trim().to_string()).collect())
 }
 
 pub fn part1(input: &[String]) -> usize {
@@ -59,15 +61,21 @@ pub fn part2(input: &[String]) -> usize {
   boxes.iter().enumerate().map(|(id,b)| b.focusing_power(id)).sum()
 }
 
+const EXAMPLE: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+
+pub const INFO: DayInfo = DayInfo{title: "Lens Library", year: 2023, day: 15, examples: &[
+  Example{input: EXAMPLE, part1: Some("1320"), part2: Some("145")}],
+  tags: &[]};
+
+crate::solution!(Vec<String>);
+
 #[cfg(test)]
 mod tests {
-  use crate::day15::{generator, part1, part2, hash};
-
-  const INPUT: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+  use crate::day15::{generator, part1, part2, hash, EXAMPLE};
 
   #[test]
   fn test_part1() {
-    assert_eq!(1320, part1(&generator(INPUT)));
+    assert_eq!(1320, part1(&generator(EXAMPLE).unwrap()));
   }
 
   #[test]
@@ -77,6 +85,6 @@ mod tests {
 
   #[test]
   fn test_part2() {
-    assert_eq!(145, part2(&generator(INPUT)));
+    assert_eq!(145, part2(&generator(EXAMPLE).unwrap()));
   }
 }
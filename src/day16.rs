@@ -1,3 +1,5 @@
+use crate::utils::{DayInfo, Dir4, Example, Grid, ParseError, Point};
+
 #[derive(Clone,Copy,Debug)]
 pub enum Mirror {
   Ground,
@@ -7,8 +9,10 @@ pub enum Mirror {
   HorizontalSplitter,
 }
 
-impl Mirror {
-  fn from_char(ch: char) -> Result<Mirror,String> {
+impl TryFrom<char> for Mirror {
+  type Error = String;
+
+  fn try_from(ch: char) -> Result<Mirror,String> {
     match ch {
       '.' => Ok(Mirror::Ground),
       '/' => Ok(Mirror::ForwardMirror),
@@ -22,33 +26,21 @@ impl Mirror {
 
 #[derive(Clone,Debug)]
 pub struct Map {
-  mirrors: Vec<Vec<Mirror>>,
-  width: usize,
-  height: usize,
+  mirrors: Grid<Mirror>,
 }
 
 impl Map {
-  fn from_str(input: &str) -> Result<Self,String> {
-    let mirrors = input.lines().map(|line| line.chars().map(Mirror::from_char)
-          .collect::<Result<Vec<Mirror>,String>>())
-        .collect::<Result<Vec<Vec<Mirror>>,String>>()?;
-    let width = mirrors[0].len();
-    let height = mirrors.len();
-    Ok(Map{mirrors, width, height})
+  fn from_str(input: &str) -> Result<Self,ParseError> {
+    Ok(Map{mirrors: Grid::parse(input)?})
   }
 
   fn get(&self, light: &Light) -> Option<Mirror> {
-    if light.x >= 0 && light.x < self.width as i32 &&
-        light.y >= 0 && light.y < self.height as i32 {
-      Some(self.mirrors[light.y as usize][light.x as usize])
-    } else {
-      None
-    }
+    self.mirrors.get(light.at.x, light.at.y).copied()
   }
 
   fn energize(&self, initial: &Light) -> usize {
     let mut pending = vec![initial.clone()];
-    let mut energized = EnergizedMap::new(self.width, self.height);
+    let mut energized = EnergizedMap::new(self.mirrors.width(), self.mirrors.height());
     while let Some(prev) = pending.pop() {
       // Are we still on the map?
       if let Some(mirror) = self.get(&prev) {
@@ -71,40 +63,31 @@ impl Map {
   }
 }
 
-pub fn generator(input: &str) -> Map {
-  Map::from_str(input).unwrap()
+pub fn generator(input: &str) -> Result<Map, ParseError> {
+  Map::from_str(input)
 }
 
 struct EnergizedMap {
-  energized: Vec<Vec<[bool; 4]>>,
+  energized: Grid<[bool; 4]>,
 }
 
 impl EnergizedMap {
   fn new(width: usize, height: usize) -> Self {
-    Self{energized: vec![vec![[false; 4]; width]; height]}
+    Self{energized: Grid::filled(width, height, [false; 4])}
   }
 
   fn mark(&mut self, light: &Light) -> bool {
-    let prev = self.energized[light.y as usize][light.x as usize][light.facing as usize];
-    self.energized[light.y as usize][light.x as usize][light.facing as usize] = true;
-    prev
+    let spot = &mut self.energized[(light.at.x as usize, light.at.y as usize)][light.facing as usize];
+    std::mem::replace(spot, true)
   }
 
   fn count(&self) -> usize {
-    let mut result = 0;
-    for row in &self.energized {
-      for spot in row {
-        if spot.iter().any(|e| *e) {
-          result += 1;
-        }
-      }
-    }
-    result
+    self.energized.iter().filter(|(_, spot)| spot.iter().any(|e| *e)).count()
   }
 
   #[allow(dead_code)]
   fn print(&self) {
-    for row in &self.energized {
+    for row in self.energized.rows() {
       for spot in row {
         let val: usize = spot.iter().enumerate()
             .map(|(i, b)| (if *b { 1 } else { 0 }) << i).sum();
@@ -115,58 +98,47 @@ impl EnergizedMap {
   }
 }
 
-#[derive(Clone,Copy,Debug,Default)]
-enum Direction {
-  #[default]
-  East,
-  North,
-  West,
-  South,
-}
-
-#[derive(Clone,Debug,Default)]
+#[derive(Clone,Debug)]
 struct Light {
-  facing: Direction,
-  x: i32,
-  y: i32,
+  facing: Dir4,
+  at: Point<i32>,
 }
 
 impl Light {
+  fn new(facing: Dir4, x: i32, y: i32) -> Self {
+    Light{facing, at: Point::new(x, y)}
+  }
+
   fn advance(&mut self) {
-    match self.facing {
-      Direction::North => self.y -= 1,
-      Direction::East => self.x += 1,
-      Direction::West => self.x -= 1,
-      Direction::South => self.y += 1,
-    }
+    self.at = self.at.step(self.facing, 1);
   }
 
   fn bounce(&mut self, mirror: Mirror) -> Option<Light> {
     match mirror {
       Mirror::ForwardMirror => {
         match self.facing {
-          Direction::North => self.facing = Direction::East,
-          Direction::East => self.facing = Direction::North,
-          Direction::South => self.facing = Direction::West,
-          Direction::West => self.facing = Direction::South,
+          Dir4::North => self.facing = Dir4::East,
+          Dir4::East => self.facing = Dir4::North,
+          Dir4::South => self.facing = Dir4::West,
+          Dir4::West => self.facing = Dir4::South,
         }
         None
       },
       Mirror::BackwardMirror => {
         match self.facing {
-          Direction::North => self.facing = Direction::West,
-          Direction::West => self.facing = Direction::North,
-          Direction::South => self.facing = Direction::East,
-          Direction::East => self.facing = Direction::South,
+          Dir4::North => self.facing = Dir4::West,
+          Dir4::West => self.facing = Dir4::North,
+          Dir4::South => self.facing = Dir4::East,
+          Dir4::East => self.facing = Dir4::South,
         }
         None
       },
       Mirror::HorizontalSplitter => {
         match self.facing {
-          Direction::North | Direction::South => {
-            self.facing = Direction::West;
+          Dir4::North | Dir4::South => {
+            self.facing = Dir4::West;
             let mut other = self.clone();
-            other.facing = Direction::East;
+            other.facing = Dir4::East;
             Some(other)
           },
           _ => None,
@@ -174,10 +146,10 @@ impl Light {
       },
       Mirror::VerticalSplitter => {
         match self.facing {
-          Direction::East | Direction::West => {
-            self.facing = Direction::North;
+          Dir4::East | Dir4::West => {
+            self.facing = Dir4::North;
             let mut other = self.clone();
-            other.facing = Direction::South;
+            other.facing = Dir4::South;
             Some(other)
           },
           _ => None,
@@ -189,31 +161,27 @@ impl Light {
 }
 
 pub fn part1(input: &Map) -> usize {
-  input.energize(&Light::default())
+  input.energize(&Light::new(Dir4::East, 0, 0))
 }
 
 pub fn part2(input: &Map) -> usize {
   let mut result = 0;
-  for x in 0..input.width as i32 {
+  for x in 0..input.mirrors.width() as i32 {
     result = result.max(input.energize(
-      &Light{facing: Direction::South, x, y: 0}));
+      &Light::new(Dir4::South, x, 0)));
     result = result.max(input.energize(
-      &Light{facing: Direction::North, x, y: input.height as i32 - 1}));
+      &Light::new(Dir4::North, x, input.mirrors.height() as i32 - 1)));
   }
-  for y in 0..input.height as i32 {
+  for y in 0..input.mirrors.height() as i32 {
     result = result.max(input.energize(
-      &Light{facing: Direction::East, y, x: 0}));
+      &Light::new(Dir4::East, 0, y)));
     result = result.max(input.energize(
-      &Light{facing: Direction::West, y, x: input.width as i32 - 1}));
+      &Light::new(Dir4::West, input.mirrors.width() as i32 - 1, y)));
   }
   result
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day16::{generator, part1, part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 ".|...\\....
 |.-.\\.....
 .....|-...
@@ -225,13 +193,23 @@ mod tests {
 .|....-|.\\
 ..//.|....";
 
+pub const INFO: DayInfo = DayInfo{title: "The Floor Will Be Lava", year: 2023, day: 16, examples: &[
+  Example{input: EXAMPLE, part1: Some("46"), part2: Some("51")}],
+  tags: &["grid"]};
+
+crate::solution!(Map);
+
+#[cfg(test)]
+mod tests {
+  use crate::day16::{generator, part1, part2, EXAMPLE};
+
   #[test]
   fn test_part1() {
-    assert_eq!(46, part1(&generator(INPUT)));
+    assert_eq!(46, part1(&generator(EXAMPLE).unwrap()));
   }
 
   #[test]
   fn test_part2() {
-    assert_eq!(51, part2(&generator(INPUT)));
+    assert_eq!(51, part2(&generator(EXAMPLE).unwrap()));
   }
 }
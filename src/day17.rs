@@ -1,7 +1,6 @@
-use std::cmp::Reverse;
-use std::collections::HashSet;
-use priority_queue::PriorityQueue;
 use smallvec::SmallVec;
+use crate::utils::{DayInfo, Dir4, Example, Grid, ParseError, Point};
+use crate::utils::search::astar_cost;
 
 type HeatValue = u32;
 type Coordinate = i16;
@@ -10,88 +9,67 @@ type Turns = u8;
 
 #[derive(Clone,Debug)]
 pub struct Map {
-  grid: Vec<Vec<HeatValue>>,
-  width: Coordinate,
-  height: Coordinate,
+  grid: Grid<HeatValue>,
+  /// The least heat lost in any block.
+  least: HeatValue,
 }
 
 impl Map {
-  fn from_str(input: &str) -> Result<Self,String> {
-    let grid = input.lines().map(|line| line.chars()
-        .map(|ch| ch.to_digit(10).map(|h| h as HeatValue)
-            .ok_or_else(||format!("Can't read number - {ch}")))
-          .collect::<Result<Vec<HeatValue>,String>>())
-        .collect::<Result<Vec<Vec<HeatValue>>,String>>()?;
-    let width = grid[0].len() as Coordinate;
-    let height = grid.len() as Coordinate;
-    Ok(Map{grid, width, height})
+  fn from_str(input: &str) -> Result<Self,ParseError> {
+    let grid = Grid::parse_with(input,
+        |ch| ch.to_digit(10).map(|h| h as HeatValue)
+            .ok_or_else(|| format!("Can't read number - {ch}")))?;
+    let least = grid.rows().flatten().copied().min().unwrap_or_default();
+    Ok(Map{grid, least})
   }
 
-  fn get_cost(&self, x: Coordinate, y: Coordinate) -> Option<HeatValue> {
-    if (0..self.width).contains(&x) && (0..self.height).contains(&y) {
-      Some(self.grid[y as usize][x as usize])
-    } else {
-      None
-    }
+  /// The bottom right corner of the map.
+  fn finish(&self) -> Point<Coordinate> {
+    Point::new(self.grid.width() as Coordinate - 1, self.grid.height() as Coordinate - 1)
   }
 
-  fn find_minimum<const MIN_TURNS: Turns, const MAX_TURNS: Turns>
-                 (&self, start: (Coordinate, Coordinate),
-                  finish: (Coordinate, Coordinate)) -> HeatValue {
-    let mut done : HashSet<Position<MIN_TURNS,MAX_TURNS>> = HashSet::new();
-    let mut pending: PriorityQueue<Position<MIN_TURNS,MAX_TURNS>, Reverse<HeatValue>> =
-        PriorityQueue::new();
-    pending.push(Position{facing: Direction::Start, straight: MIN_TURNS,
-      x: start.0, y: start.1}, Reverse(0));
-    while let Some((position, Reverse(heat))) = pending.pop() {
-      if position.straight >= MIN_TURNS && position.x == finish.0 && position.y == finish.1 {
-        return heat
-      }
-      for next in position.next() {
-        if !done.contains(&next) {
-          if let Some(cost) = self.get_cost(next.x, next.y) {
-            pending.push_increase(next, Reverse(heat + cost));
-          }
-        }
-      }
-      done.insert(position);
-    }
-    HeatValue::MAX
+  fn get_cost(&self, at: Point<Coordinate>) -> Option<HeatValue> {
+    self.grid.get(at.x, at.y).copied()
   }
-}
 
-pub fn generator(input: &str) -> Map {
-  Map::from_str(input).unwrap()
+  /// The moves from the position that stay on the map and the heat lost by each.
+  fn moves<const MIN: Turns, const MAX: Turns>(&self, position: &Position<MIN, MAX>)
+      -> impl Iterator<Item = (Position<MIN, MAX>, HeatValue)> + '_ {
+    position.next().into_iter()
+        .filter_map(|next| self.get_cost(next.at).map(|cost| (next, cost)))
+  }
+
+  /// Find the least heat lost on the way from start to finish. The distance
+  /// to the finish times the least heat lost in any block is a lower bound.
+  fn find_minimum<const MIN_TURNS: Turns, const MAX_TURNS: Turns>
+                 (&self, start: Point<Coordinate>, finish: Point<Coordinate>) -> HeatValue {
+    let start: Position<MIN_TURNS, MAX_TURNS> =
+        Position{facing: None, straight: MIN_TURNS, at: start};
+    astar_cost(start,
+               |position| self.moves(position),
+               |position| position.at.manhattan(finish) as HeatValue * self.least,
+               |position| position.straight >= MIN_TURNS && position.at == finish)
+        .unwrap_or(HeatValue::MAX)
+  }
 }
 
-#[derive(Clone,Copy,Debug,Hash,Eq,PartialEq)]
-enum Direction {
-  East,
-  North,
-  West,
-  South,
-  Start,
+pub fn generator(input: &str) -> Result<Map, ParseError> {
+  Map::from_str(input)
 }
 
 #[derive(Clone,Debug,Eq,Hash,PartialEq)]
 struct Position<const MIN: Turns, const MAX: Turns> {
-  facing: Direction,
+  /// The direction of the last move, which is None at the start.
+  facing: Option<Dir4>,
   straight: Turns,
-  x: Coordinate,
-  y: Coordinate,
+  at: Point<Coordinate>,
 }
 
 impl<const MIN: Turns, const MAX: Turns> Position<MIN, MAX> {
-  fn step(&self, facing: Direction) -> Option<Self> {
+  fn step(&self, facing: Dir4) -> Option<Self> {
     let mut work = self.clone();
-    match facing {
-      Direction::North => work.y -= 1,
-      Direction::East => work.x += 1,
-      Direction::South => work.y += 1,
-      Direction::West => work.x -= 1,
-      _ => panic!("Bad direction {:?}", facing),
-    }
-    if work.facing == facing {
+    work.at = work.at.step(facing, 1);
+    if work.facing == Some(facing) {
       work.straight += 1;
       if work.straight > MAX {
         return None
@@ -100,7 +78,7 @@ impl<const MIN: Turns, const MAX: Turns> Position<MIN, MAX> {
       if work.straight < MIN {
         return None
       }
-      work.facing = facing;
+      work.facing = Some(facing);
       work.straight = 1;
     }
     Some(work)
@@ -109,29 +87,22 @@ impl<const MIN: Turns, const MAX: Turns> Position<MIN, MAX> {
   fn next(&self) -> SmallVec<[Self;3]> {
     // What are the potential directions to move?
     let dirs = match self.facing {
-      Direction::East => &[Direction::East, Direction::North, Direction::South],
-      Direction::North => &[Direction::North, Direction::East, Direction::West],
-      Direction::West => &[Direction::West, Direction::North, Direction::South],
-      Direction::South => &[Direction::South, Direction::East, Direction::West],
-      Direction::Start => &[Direction::East, Direction::South, Direction::West],
+      Some(dir) => [dir, dir.turn_left(), dir.turn_right()],
+      None => [Dir4::East, Dir4::South, Dir4::West],
     };
     dirs.iter().filter_map(|d| self.step(*d)).collect()
   }
 }
 
 pub fn part1(input: &Map) -> HeatValue {
-  input.find_minimum::<1,3>((0, 0), (input.width - 1, input.height - 1))
+  input.find_minimum::<1,3>(Point::default(), input.finish())
 }
 
 pub fn part2(input: &Map) -> HeatValue {
-  input.find_minimum::<4,10>((0, 0), (input.width - 1, input.height - 1))
+  input.find_minimum::<4,10>(Point::default(), input.finish())
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day17::{generator, part1, part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "2413432311323
 3215453535623
 3255245654254
@@ -146,13 +117,54 @@ mod tests {
 2546548887735
 4322674655533";
 
+const EXAMPLE2: &str =
+"111111111111
+999999999991
+999999999991
+999999999991
+999999999991";
+
+pub const INFO: DayInfo = DayInfo{title: "Clumsy Crucible", year: 2023, day: 17, examples: &[
+  Example{input: EXAMPLE, part1: Some("102"), part2: Some("94")},
+  Example{input: EXAMPLE2, part1: None, part2: Some("71")}],
+  tags: &["grid", "graph"]};
+
+crate::solution!(Map);
+
+#[cfg(test)]
+mod tests {
+  use crate::day17::{generator, part1, part2, Position, EXAMPLE, EXAMPLE2};
+  use crate::utils::search::dijkstra;
+
   #[test]
   fn test_part1() {
-    assert_eq!(102, part1(&generator(INPUT)));
+    assert_eq!(102, part1(&generator(EXAMPLE).unwrap()));
   }
 
   #[test]
   fn test_part2() {
-    assert_eq!(94, part2(&generator(INPUT)));
+    assert_eq!(94, part2(&generator(EXAMPLE).unwrap()));
+    assert_eq!(71, part2(&generator(EXAMPLE2).unwrap()));
+  }
+
+  #[test]
+  fn test_matches_dijkstra() {
+    for input in [EXAMPLE, EXAMPLE2] {
+      let map = generator(input).unwrap();
+      let finish = map.finish();
+      let start: Position<4, 10> = Position{facing: None, straight: 4, at: Default::default()};
+      let found = dijkstra(start, |p| map.moves(p), |p| p.straight >= 4 && p.at == finish);
+      assert_eq!(part2(&map), found.unwrap().cost);
+      let start: Position<1, 3> = Position{facing: None, straight: 1, at: Default::default()};
+      let found = dijkstra(start, |p| map.moves(p), |p| p.straight >= 1 && p.at == finish);
+      assert_eq!(part1(&map), found.unwrap().cost);
+    }
+  }
+
+  #[test]
+  fn test_zero_heat() {
+    let map = generator("1900\n1091\n0001").unwrap();
+    assert_eq!(0, map.least);
+    assert_eq!(2, part1(&map));
   }
 }
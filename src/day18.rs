@@ -1,92 +1,68 @@
 use std::cmp::Ordering;
 use std::ops::Range;
 use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, Stroke, Transform};
+use crate::utils::{DayInfo, Dir4, Example, ParseError, Point};
 
 type Coordinate = i32;
 type Color = u32;
 
-#[derive(Clone,Copy,Debug,PartialEq)]
-pub enum Direction {
-  Up,
-  Right,
-  Down,
-  Left,
-}
-
-impl Direction {
-  fn from_str(s: &str) -> Result<Self, String> {
-    match s {
-      "R" => Ok(Direction::Right),
-      "L" => Ok(Direction::Left),
-      "D" => Ok(Direction::Down),
-      "U" => Ok(Direction::Up),
-      _ => Err(format!("Unknown direction - {s}")),
-    }
-  }
-
-  /// The number to separate out the direction from distance in the color.
-  const COLOR_DIVISOR: Color = 16;
-
-  fn from_color(c: Color) -> Result<Self,String> {
-    match c % Self::COLOR_DIVISOR {
-      0 => Ok(Direction::Right),
-      1 => Ok(Direction::Down),
-      2 => Ok(Direction::Left),
-      3 => Ok(Direction::Up),
-      _ => Err(format!("Unknown direction {} from {c}", c % Self::COLOR_DIVISOR)),
-    }
+fn parse_direction(s: &str) -> Result<Dir4, ParseError> {
+  match s {
+    "R" => Ok(Dir4::East),
+    "L" => Ok(Dir4::West),
+    "D" => Ok(Dir4::South),
+    "U" => Ok(Dir4::North),
+    _ => Err(ParseError::new(s, "Unknown direction")),
   }
 }
 
-#[derive(Clone,Debug,Default,PartialEq)]
-pub struct Position {
-  x: Coordinate,
-  y: Coordinate,
-}
+/// The number to separate out the direction from distance in the color.
+const COLOR_DIVISOR: Color = 16;
 
-impl Position {
-  fn move_to(&mut self, direction: Direction, distance: Coordinate) {
-    match direction {
-      Direction::Up => self.y -= distance,
-      Direction::Right => self.x += distance,
-      Direction::Down => self.y += distance,
-      Direction::Left => self.x -= distance,
-    }
+fn direction_from_color(c: Color) -> Result<Dir4, String> {
+  match c % COLOR_DIVISOR {
+    0 => Ok(Dir4::East),
+    1 => Ok(Dir4::South),
+    2 => Ok(Dir4::West),
+    3 => Ok(Dir4::North),
+    _ => Err(format!("Unknown direction {} from {c}", c % COLOR_DIVISOR)),
   }
 }
 
+type Position = Point<Coordinate>;
+
 #[derive(Clone,Debug)]
 pub struct Edge {
   start: Position,
-  direction: Direction,
+  direction: Dir4,
   end: Position,
   color: Color,
 }
 
 impl Edge {
-  fn from_str(line: &str, start: Position) -> Result<Self,String> {
+  fn from_str(line: &str, start: Position) -> Result<Self,ParseError> {
     let mut words = line.split_whitespace();
-    let direction = Direction::from_str(
-      words.next().ok_or(format!("Can't find direction in {line}."))?)?;
-    let distance = words.next()
-        .ok_or(format!("Can't find distance in {line}"))?
-        .parse::<Coordinate>().map_err(|_| format!("Can't parse distance in {line}"))?;
-    let mut color = words.next().ok_or(format!("Can't find color in {line}"))?;
-    color = color.strip_prefix("(#").ok_or("can't remove color prefix".to_string())?;
-    color = color.strip_suffix(')').ok_or("can't remove color suffix".to_string())?;
+    let direction = parse_direction(words.next()
+        .ok_or_else(|| ParseError::new(line, "Can't find direction"))?)?;
+    let distance_str = words.next()
+        .ok_or_else(|| ParseError::new(line, "Can't find distance"))?;
+    let distance = distance_str.parse::<Coordinate>()
+        .map_err(|_| ParseError::new(distance_str, "Can't parse distance"))?;
+    let color_str = words.next().ok_or_else(|| ParseError::new(line, "Can't find color"))?;
+    let color = color_str.strip_prefix("(#")
+        .ok_or_else(|| ParseError::new(color_str, "can't remove color prefix"))?;
+    let color = color.strip_suffix(')')
+        .ok_or_else(|| ParseError::new(color_str, "can't remove color suffix"))?;
     let color = Color::from_str_radix(color, 16)
-        .map_err(|_| format!("Can't parse color - {color}"))?;
-    let mut end = start.clone();
-    end.move_to(direction, distance);
-    Ok(Edge{start: start.clone(), direction, end, color})
+        .map_err(|_| ParseError::new(color, "Can't parse color"))?;
+    let end = start.step(direction, distance);
+    Ok(Edge{start, direction, end, color})
   }
 
-  fn from_color(color: Color, start: &Position) -> Result<Self,String> {
-    let direction = Direction::from_color(color)?;
-    let mut end = start.clone();
-    let distance = (color / Direction::COLOR_DIVISOR) as Coordinate;
-    end.move_to(direction, distance);
-    Ok(Edge{start: start.clone(), direction, end, color: 0})
+  fn from_color(color: Color, start: Position) -> Result<Self,String> {
+    let direction = direction_from_color(color)?;
+    let distance = (color / COLOR_DIVISOR) as Coordinate;
+    Ok(Edge{start, direction, end: start.step(direction, distance), color: 0})
   }
 }
 
@@ -187,23 +163,23 @@ pub struct Map {
 }
 
 impl Map {
-  fn from_str(input: &str) -> Result<Self,String> {
+  fn from_str(input: &str) -> Result<Self,ParseError> {
     let mut current = Position::default();
     let mut left = 0;
     let mut right = 0;
     let mut top = 0;
     let mut bottom = 0;
     let edges = input.lines().map(|l| {
-      let e = Edge::from_str(l, current.clone());
+      let e = Edge::from_str(l, current);
       if let Ok(edge) = &e {
         left = left.min(edge.end.x);
         right = right.max(edge.end.x);
         top = top.min(edge.end.y);
         bottom = bottom.max(edge.end.y);
-        current = edge.end.clone();
+        current = edge.end;
       }
       e
-    }).collect::<Result<Vec<Edge>,String>>()?;
+    }).collect::<Result<Vec<Edge>,ParseError>>()?;
     Ok(Map{edges, width: left..right+1, height: top..bottom+1})
   }
 
@@ -214,17 +190,17 @@ impl Map {
     let mut edges = Vec::new();
     for e in &self.edges {
       match e.direction {
-        Direction::Up =>
+        Dir4::North =>
           // Don't include the southern end point.
           edges.push(EdgeBox{x: e.start.x..e.start.x+1,
             y: e.end.y..e.start.y, is_vertical: true}),
-        Direction::Down =>
+        Dir4::South =>
           edges.push(EdgeBox{x: e.start.x..e.start.x+1,
             y: e.start.y..e.end.y, is_vertical: true}),
-        Direction::Right =>
+        Dir4::East =>
           edges.push(EdgeBox{y: e.start.y..e.start.y+1,
             x: e.start.x..e.end.x+1, is_vertical: false}),
-        Direction::Left =>
+        Dir4::West =>
           edges.push(EdgeBox{y: e.start.y..e.start.y+1,
             x: e.end.x..e.start.x+1, is_vertical: false}),
       }
@@ -241,13 +217,13 @@ impl Map {
     let mut top = 0;
     let mut bottom = 1;
     let edges = self.edges.iter().map(|original_edge| {
-      let e = Edge::from_color(original_edge.color, &current);
+      let e = Edge::from_color(original_edge.color, current);
       if let Ok(edge) = &e {
         left = left.min(edge.end.x);
         right = right.max(edge.end.x + 1);
         top = top.min(edge.end.y);
         bottom = bottom.max(edge.end.y + 1);
-        current = edge.end.clone();
+        current = edge.end;
       }
       e
     }).collect::<Result<Vec<Edge>,String>>()?;
@@ -255,8 +231,8 @@ impl Map {
   }
 }
 
-pub fn generator(input: &str) -> Map {
-  Map::from_str(input).unwrap()
+pub fn generator(input: &str) -> Result<Map, ParseError> {
+  Map::from_str(input)
 }
 
 pub fn part1(input: &Map) -> u64 {
@@ -295,11 +271,7 @@ pub fn save_picture(input: &Map, filename: &str) {
   pixmap.save_png(filename).unwrap()
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day18::{generator, part1, part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "R 6 (#70c710)
 D 5 (#0dc571)
 L 2 (#5713f0)
@@ -315,13 +287,23 @@ U 3 (#a77fa3)
 L 2 (#015232)
 U 2 (#7a21e3)";
 
+pub const INFO: DayInfo = DayInfo{title: "Lavaduct Lagoon", year: 2023, day: 18, examples: &[
+  Example{input: EXAMPLE, part1: Some("62"), part2: Some("952408144115")}],
+  tags: &[]};
+
+crate::solution!(Map);
+
+#[cfg(test)]
+mod tests {
+  use crate::day18::{generator, part1, part2, EXAMPLE};
+
   #[test]
   fn test_part1() {
-    assert_eq!(62, part1(&generator(INPUT)));
+    assert_eq!(62, part1(&generator(EXAMPLE).unwrap()));
   }
 
   #[test]
   fn test_part2() {
-    assert_eq!(952408144115, part2(&generator(INPUT)));
+    assert_eq!(952408144115, part2(&generator(EXAMPLE).unwrap()));
   }
 }
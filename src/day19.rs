@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::fmt::Display;
 use std::ops::Range;
+use crate::utils::{DayInfo, Example, IntervalSet, ParseError, blocks};
 
 type Rating = i16;
 type RuleId = u32;
@@ -14,13 +15,13 @@ pub enum AttributeId {
 }
 
 impl AttributeId {
-  fn from_str(s: &str) -> Result<Self,String> {
+  fn from_str(s: &str) -> Result<Self,ParseError> {
     match s {
       "x" => Ok(AttributeId::X),
       "m" => Ok(AttributeId::M),
       "a" => Ok(AttributeId::A),
       "s" => Ok(AttributeId::S),
-      _ => Err(format!("Unknown attribute {s}")),
+      _ => Err(ParseError::new(s, "Unknown attribute")),
     }
   }
 }
@@ -33,15 +34,14 @@ pub struct Part {
 }
 
 impl Part {
-  fn from_str(s: &str) -> Result<Self,String> {
+  fn from_str(s: &str) -> Result<Self,ParseError> {
     let mut result = Self::default();
-    for phrase in s.strip_prefix('{').ok_or("No open bracket")?
-        .strip_suffix('}').ok_or("No closing bracket")?
+    for phrase in s.strip_prefix('{').ok_or_else(|| ParseError::new(s, "No open bracket"))?
+        .strip_suffix('}').ok_or_else(|| ParseError::new(s, "No closing bracket"))?
         .split(',') {
       let (attr, value) = phrase.split_once('=')
-          .ok_or(format!("Can't parse {phrase}"))?;
-      result.ratings[AttributeId::from_str(attr)? as usize] =
-          value.parse::<Rating>().map_err(|_| format!("can't parse integer {value}"))?;
+          .ok_or_else(|| ParseError::new(phrase, "Can't parse rating"))?;
+      result.ratings[AttributeId::from_str(attr)? as usize] = parse_rating(value)?;
     }
     Ok(result)
   }
@@ -59,17 +59,18 @@ pub enum Target {
 }
 
 impl Target {
-  fn from_str(s: &str, names: &HashMap<String,RuleId>) -> Result<Self,String> {
+  fn from_str(s: &str, names: &HashMap<String,RuleId>) -> Result<Self,ParseError> {
     match s {
       "A" => Ok(Target::Accept),
       "R" => Ok(Target::Reject),
-      rule => Ok(Target::GoTo(*names.get(rule).ok_or(format!("Can't find rule {rule}"))?))
+      rule => Ok(Target::GoTo(*names.get(rule)
+          .ok_or_else(|| ParseError::new(rule, "Can't find rule"))?)),
     }
   }
 }
 
-fn parse_rating(s: &str) -> Result<Rating,String> {
-  s.parse().map_err(|_| format!("Can't parse integer - {s}"))
+fn parse_rating(s: &str) -> Result<Rating,ParseError> {
+  s.parse().map_err(|_| ParseError::new(s, "Can't parse integer"))
 }
 
 #[derive(Clone,Debug)]
@@ -79,13 +80,13 @@ pub enum Operator {
 }
 
 impl Operator {
-  fn from_str(s: &str) -> Result<Self,String> {
+  fn from_str(s: &str) -> Result<Self,ParseError> {
     if let Some(tail) = s.strip_prefix('<') {
       Ok(Operator::Less(parse_rating(tail)?))
     } else if let Some(tail) = s.strip_prefix('>') {
       Ok(Operator::Greater(parse_rating(tail)?))
     } else {
-      Err(format!("Can't find operator in {s}"))
+      Err(ParseError::new(s, "Can't find operator"))
     }
   }
 
@@ -119,11 +120,12 @@ pub struct RuleCondition {
 }
 
 impl RuleCondition {
-  fn from_str(s: &str, names: &HashMap<String,RuleId>) -> Result<Self,String> {
+  fn from_str(s: &str, names: &HashMap<String,RuleId>) -> Result<Self,ParseError> {
     let (cond, target_name) = s.split_once(':')
-        .ok_or("Can't find target in {s}")?;
-    let attribute = AttributeId::from_str(&cond[..1])?;
-    let operator = Operator::from_str(&cond[1..])?;
+        .ok_or_else(|| ParseError::new(s, "Can't find target"))?;
+    let split = cond.char_indices().nth(1).map_or(cond.len(), |(i, _)| i);
+    let attribute = AttributeId::from_str(&cond[..split])?;
+    let operator = Operator::from_str(&cond[split..])?;
     let target = Target::from_str(target_name, names)?;
     Ok(RuleCondition{attribute,operator,target})
   }
@@ -146,17 +148,17 @@ pub struct Rule {
 }
 
 impl Rule {
-  fn from_str(s: &str, names: &HashMap<String, RuleId>) -> Result<Self,String> {
+  fn from_str(s: &str, names: &HashMap<String, RuleId>) -> Result<Self,ParseError> {
     let (name, definition) = s.split_once('{')
-        .ok_or("Can't find definition in {s}")?;
+        .ok_or_else(|| ParseError::new(s, "Can't find definition"))?;
     let definition = definition.strip_suffix('}')
-        .ok_or(format!("Can't find closing brace in {s}"))?;
+        .ok_or_else(|| ParseError::new(s, "Can't find closing brace"))?;
     let conds = definition.split(',').collect::<Vec<&str>>();
     let conditions = conds[..conds.len()-1].iter()
         .map(|s| RuleCondition::from_str(s, names))
-        .collect::<Result<Vec<RuleCondition>,String>>()?;
+        .collect::<Result<Vec<RuleCondition>,ParseError>>()?;
     let last = Target::from_str(conds.last()
-        .ok_or(format!("No definitions in {definition}"))?, names)?;
+        .ok_or_else(|| ParseError::new(definition, "No definitions"))?, names)?;
     Ok(Rule{name: name.to_string(), conditions, last})
   }
 
@@ -178,21 +180,22 @@ pub struct Input {
 }
 
 impl Input {
-  fn from_str(s: &str) -> Result<Self,String> {
-    let (rule_str, part_str) = s.split_once("\n\n")
-        .ok_or("can't find part lists")?;
+  fn from_str(s: &str) -> Result<Self,ParseError> {
+    let mut itr = blocks(s);
+    let (rule_str, part_str) = itr.next().zip(itr.next())
+        .ok_or_else(|| ParseError::new(s, "can't find part lists"))?;
     let names = rule_str.lines().enumerate()
         .map(|(i, line)|
           line.split_once('{')
-              .ok_or(format!("can't find attribute name in {line}"))
+              .ok_or_else(|| ParseError::new(line, "can't find attribute name"))
               .map(|(name, _)| (name.to_string(), i as RuleId)))
-        .collect::<Result<HashMap<String, RuleId>,String>>()?;
+        .collect::<Result<HashMap<String, RuleId>,ParseError>>()?;
     let rule_set = rule_str.lines().map(|s| Rule::from_str(s, &names))
-        .collect::<Result<Vec<Rule>,String>>()?;
+        .collect::<Result<Vec<Rule>,ParseError>>()?;
     let parts = part_str.lines().map(Part::from_str)
-        .collect::<Result<Vec<Part>,String>>()?;
+        .collect::<Result<Vec<Part>,ParseError>>()?;
     Ok(Input{rule_set, parts,
-      start_rule: *names.get("in").ok_or("Can't find in rule".to_string())?})
+      start_rule: *names.get("in").ok_or_else(|| ParseError::new(rule_str, "Can't find in rule"))?})
   }
 
   pub fn accept(&self, part: &Part) -> bool {
@@ -211,30 +214,21 @@ const ATTRIBUTE_RANGE: Range<Rating> = 1..4001;
 
 #[derive(Clone,Debug)]
 struct SymbolicValue {
-  ranges: Vec<Range<Rating>>,
+  ranges: IntervalSet<Rating>,
 }
 
 impl SymbolicValue {
   fn default() -> Self {
-    SymbolicValue{ranges: vec![ATTRIBUTE_RANGE]}
+    SymbolicValue{ranges: IntervalSet::from(ATTRIBUTE_RANGE)}
   }
 
   fn count(&self) -> u64 {
-    self.ranges.iter().map(|r| r.len() as u64).sum()
+    self.ranges.len() as u64
   }
 
   /// Restrict the current range by the new range.
   fn and(&self, range: &Range<Rating>) -> Self {
-    let mut ranges = Vec::new();
-    for old_range in &self.ranges {
-      if range.end > old_range.start {
-        ranges.push(range.start.max(old_range.start)..range.end.min(old_range.end))
-      } else if old_range.end > range.start {
-        ranges.push(range.start..old_range.end);
-      }
-    }
-    ranges.retain(|r| !r.is_empty());
-    SymbolicValue{ranges}
+    SymbolicValue{ranges: self.ranges.intersection(&IntervalSet::from(range.clone()))}
   }
 
   fn is_empty(&self) -> bool {
@@ -270,8 +264,9 @@ impl SymbolicAttributes {
 
 impl Display for SymbolicAttributes {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    write!(f, "[X: {:?}, M: {:?} A: {:?} S: {:?}",self.attributes[0].ranges,
-           self.attributes[1].ranges, self.attributes[2].ranges, self.attributes[3].ranges)
+    write!(f, "[X: {:?}, M: {:?} A: {:?} S: {:?}",self.attributes[0].ranges.ranges(),
+           self.attributes[1].ranges.ranges(), self.attributes[2].ranges.ranges(),
+           self.attributes[3].ranges.ranges())
   }
 }
 
@@ -335,8 +330,8 @@ impl Display for State {
   }
 }
 
-pub fn generator(input: &str) -> Input {
-  Input::from_str(input).unwrap()
+pub fn generator(input: &str) -> Result<Input, ParseError> {
+  Input::from_str(input)
 }
 
 pub fn part1(input: &Input) -> i64 {
@@ -347,11 +342,7 @@ pub fn part2(input: &Input) -> u64 {
   State::new(input).count(input)
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day19::{generator, part1, part2, SymbolicValue};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "px{a<2006:qkq,m>2090:A,rfg}
 pv{a>1716:R,A}
 lnx{m>1548:A,A}
@@ -370,27 +361,38 @@ hdj{m>838:A,pv}
 {x=2461,m=1339,a=466,s=291}
 {x=2127,m=1623,a=2188,s=1013}";
 
+pub const INFO: DayInfo = DayInfo{title: "Aplenty", year: 2023, day: 19, examples: &[
+  Example{input: EXAMPLE, part1: Some("19114"), part2: Some("167409079868000")}],
+  tags: &[]};
+
+crate::solution!(Input);
+
+#[cfg(test)]
+mod tests {
+  use crate::day19::{generator, part1, part2, SymbolicValue, EXAMPLE};
+  use crate::utils::IntervalSet;
+
   #[test]
   fn test_part1() {
-    assert_eq!(19114, part1(&generator(INPUT)));
+    assert_eq!(19114, part1(&generator(EXAMPLE).unwrap()));
   }
 
   #[test]
   fn test_conditions() {
     let test_value = SymbolicValue::default();
-    assert_eq!("SymbolicValue { ranges: [1000..4001] }",
-               format!("{:?}", test_value.and(&(1000..5000))));
-    assert_eq!("SymbolicValue { ranges: [1..123] }",
-               format!("{:?}", test_value.and(&(-1000..123))));
-    assert_eq!("SymbolicValue { ranges: [1..4001] }",
-               format!("{:?}", test_value.and(&(-1000..5000))));
-    assert_eq!("SymbolicValue { ranges: [100..500] }",
-               format!("{:?}", test_value.and(&(100..500))));
-    assert_eq!(true, test_value.and(&(4001..5000)).is_empty());
+    assert_eq!(IntervalSet::from(1000..4001), test_value.and(&(1000..5000)).ranges);
+    assert_eq!(IntervalSet::from(1..123), test_value.and(&(-1000..123)).ranges);
+    assert_eq!(IntervalSet::from(1..4001), test_value.and(&(-1000..5000)).ranges);
+    assert_eq!(IntervalSet::from(100..500), test_value.and(&(100..500)).ranges);
+    assert!(test_value.and(&(4001..5000)).is_empty());
+    let split = test_value.and(&(1..100)).ranges.union(&IntervalSet::from(3000..4001));
+    let value = SymbolicValue{ranges: split};
+    assert_eq!(&[50..100, 3000..3500], value.and(&(50..3500)).ranges.ranges());
+    assert_eq!(1100, value.count());
   }
 
   #[test]
   fn test_part2() {
-    assert_eq!(167409079868000, part2(&generator(INPUT)));
+    assert_eq!(167409079868000, part2(&generator(EXAMPLE).unwrap()));
   }
 }
@@ -1,3 +1,5 @@
+use crate::utils::{DayInfo, Example, ParseError};
+
 #[derive(Clone,Debug)]
 pub struct Draw {
   red: i32,
@@ -5,23 +7,23 @@ pub struct Draw {
   green: i32,
 }
 
-fn parse_int(s: &str) -> Result<i32, String> {
-  s.parse().map_err(|_| format!("Can't parse integer - {s}"))
+fn parse_int(s: &str) -> Result<i32, ParseError> {
+  s.parse().map_err(|_| ParseError::new(s, "Can't parse integer"))
 }
 
 impl Draw {
-  fn from_str(s: &str) -> Result<Self, String> {
+  fn from_str(s: &str) -> Result<Self, ParseError> {
     let mut red = 0;
     let mut blue = 0;
     let mut green = 0;
     for draw_str in s.split(", ") {
       let (count, color) = draw_str.split_once(' ')
-        .ok_or("1 word in term")?;
+        .ok_or_else(|| ParseError::new(draw_str, "1 word in term"))?;
       match color {
         "red" => red += parse_int(count)?,
         "blue" => blue += parse_int(count)?,
         "green" => green += parse_int(count)?,
-        _ => return Err(format!("Unknown color: {color}")),
+        _ => return Err(ParseError::new(color, "Unknown color")),
       }
     }
     Ok(Draw{red, blue, green})
@@ -35,11 +37,13 @@ pub struct Game {
 }
 
 impl Game {
-  fn from_str(s: &str) -> Result<Self,String> {
-    let (title, draw_string) = s.split_once(": ").ok_or("Can't parse game")?;
-    let id = parse_int(title.split_whitespace().nth(1).ok_or("Can't parse title")?)?;
+  fn from_str(s: &str) -> Result<Self,ParseError> {
+    let (title, draw_string) = s.split_once(": ")
+      .ok_or_else(|| ParseError::new(s, "Can't parse game"))?;
+    let id = parse_int(title.split_whitespace().nth(1)
+      .ok_or_else(|| ParseError::new(title, "Can't parse title"))?)?;
     let draws = draw_string.split("; ")
-      .map(Draw::from_str).collect::<Result<Vec<Draw>, String>>()?;
+      .map(Draw::from_str).collect::<Result<Vec<Draw>, ParseError>>()?;
     Ok(Game{id, draws})
   }
 
@@ -56,12 +60,8 @@ impl Game {
   }
 }
 
-pub fn generator(input: &str) -> Vec<Game> {
-  input.lines()
-    .map(|l| Game::from_str(l)
-      .map_err(|e| format!("Can't parse game with error [{e}] in {l}")))
-    .collect::<Result<Vec<Game>, String>>()
-    .unwrap() // panics on error
+pub fn generator(input: &str) -> Result<Vec<Game>, ParseError> {
+  input.lines().map(Game::from_str).collect()
 }
 
 static MAX_DRAW: Draw = Draw{red: 12, green: 13, blue: 14};
@@ -81,24 +81,30 @@ pub fn part2(input: &[Game]) -> i32 {
     .sum()
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day2::{generator, part1, part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
 Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
 Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
+pub const INFO: DayInfo = DayInfo{title: "Cube Conundrum", year: 2023, day: 2, examples: &[
+  Example{input: EXAMPLE, part1: Some("8"), part2: Some("2286")}],
+  tags: &[]};
+
+crate::solution!(Vec<Game>);
+
+#[cfg(test)]
+mod tests {
+  use crate::day2::{generator, part1, part2, EXAMPLE};
+
   #[test]
   fn test_part1() {
-    assert_eq!(8, part1(&generator(INPUT)));
+    assert_eq!(8, part1(&generator(EXAMPLE).unwrap()));
   }
 
   #[test]
   fn test_part2() {
-    assert_eq!(2286, part2(&generator(INPUT)));
+    assert_eq!(2286, part2(&generator(EXAMPLE).unwrap()));
   }
 }
@@ -5,6 +5,8 @@ use std::fs::File;
 use std::io::prelude::*;
 use itertools::Itertools;
 use num_integer::Integer;
+use crate::utils::{DayInfo, Example, ParseError};
+use crate::utils::cycle::{find_cycle, Cycle, Memory};
 
 #[derive(Clone,Copy,Debug,Eq,PartialEq)]
 pub enum ModuleKind {
@@ -32,9 +34,9 @@ pub struct Module {
 
 impl Module {
   fn from_str(s: &str, names: &HashMap<String,usize>,
-              input_counts: &mut [usize]) -> Result<Self,String> {
+              input_counts: &mut [usize]) -> Result<Self,ParseError> {
     let (full_name, target) = s.split_once(" -> ")
-        .ok_or(format!("Can't parse module - {s}"))?;
+        .ok_or_else(|| ParseError::new(s, "Can't parse module"))?;
     let name;
     let kind;
     if let Some(tail) = full_name.strip_prefix('%') {
@@ -47,7 +49,7 @@ impl Module {
       name = full_name;
       kind = ModuleKind::Broadcast;
     } else {
-      return Err(format!("Can't determine kind in {s}"));
+      return Err(ParseError::new(full_name, "Can't determine kind"));
     }
     let outputs = target.split(", ")
         .map(|s| names.get(s).map(|&u| {
@@ -59,7 +61,7 @@ impl Module {
 
   fn sends_to(&self, target: usize) -> bool {
     self.outputs.iter().any(|e|
-        e.as_ref().map_or(false, |edge| edge.target == target))
+        e.as_ref().is_some_and(|edge| edge.target == target))
   }
 }
 
@@ -72,11 +74,11 @@ pub struct Configuration {
 impl Configuration {
   const FINAL_STATE_NAME: &'static str = "rx";
 
-  fn from_str(s: &str) -> Result<Self,String> {
+  fn from_str(s: &str) -> Result<Self,ParseError> {
     let mut names = HashMap::new();
     for (i, line) in s.lines().enumerate() {
       let (name, _) = line.split_once(" -> ")
-          .ok_or(format!("Can't find name in {line}"))?;
+          .ok_or_else(|| ParseError::new(line, "Can't find name"))?;
       let mut name = name.to_string();
       if name.starts_with('%') || name.starts_with('&') {
         name.remove(0);
@@ -87,11 +89,12 @@ impl Configuration {
     if adding_final_state {
       names.insert(Self::FINAL_STATE_NAME.to_string(), names.len());
     }
-    let broadcaster = *names.get("broadcaster").ok_or("Can't find broadcaster")?;
+    let broadcaster = *names.get("broadcaster")
+        .ok_or_else(|| ParseError::new(s, "Can't find broadcaster"))?;
     let mut input_counts = vec![0; names.len()];
     let mut modules = s.lines()
         .map(|l| Module::from_str(l, &names, &mut input_counts))
-        .collect::<Result<Vec<Module>,String>>()?;
+        .collect::<Result<Vec<Module>,ParseError>>()?;
     if adding_final_state {
       modules.push(Module{name: Self::FINAL_STATE_NAME.to_string(),
         kind: ModuleKind::Output, outputs: Vec::new(), input_count: 0})
@@ -169,7 +172,7 @@ impl Configuration {
     result
   }
 
-  fn compute_partitions(&self) -> Option<Vec<Subgraph>> {
+  fn compute_partitions(&self) -> Option<Vec<Subgraph<'_>>> {
     if let [output] = &self.find_output_modules()[..] {
       if let [conditional] = &self.find_inputs(*output)[..] {
         if self.get_kind(*conditional) == ModuleKind::Conjunction {
@@ -189,7 +192,6 @@ impl Configuration {
 trait Graph {
   fn start(&self) -> usize;
   fn next(&self, node: usize) -> &[Option<Edge>];
-  fn is_exit(&self, node: usize) -> bool;
   fn num_nodes(&self) -> usize;
   fn get_name(&self, node: usize) -> &str;
   fn get_kind(&self, node: usize) -> ModuleKind;
@@ -205,10 +207,6 @@ impl Graph for Configuration {
     &self.modules[node].outputs
   }
 
-  fn is_exit(&self, node: usize) -> bool {
-    self.modules[node].kind == ModuleKind::Output
-  }
-
   fn num_nodes(&self) -> usize {
     self.modules.len()
   }
@@ -298,6 +296,14 @@ impl<'a> FlowState<'a> {
     self.stabilize(trace);
   }
 
+  /// Push the button on the given module states instead of our own, which
+  /// leaves the new states in them.
+  fn push_button_on(&mut self, states: &mut Vec<State>) {
+    std::mem::swap(&mut self.states, states);
+    self.push_button(false);
+    std::mem::swap(&mut self.states, states);
+  }
+
   fn stabilize(&mut self, trace: bool) {
     let mut count: usize = 0;
     while let Some(message) = self.pending.pop_front() {
@@ -354,7 +360,6 @@ impl<'a> FlowState<'a> {
 #[derive(Debug)]
 struct Subgraph<'a> {
   graph: &'a Configuration,
-  exit: usize,
   /// Indexed by module number in the graph, contains id number in subgraph.
   translation: Vec<usize>,
   /// Indexed by subgraph node id, leaving edges
@@ -387,39 +392,34 @@ impl<'a> Subgraph<'a> {
                         .map(|target| Edge{target, ..*e})))
                 .collect())
         .collect::<Vec<Vec<Option<Edge>>>>();
-    let mut output = exit;
     // Add the output node to the subgraph and connect the exit node
     // to it.
     if let Some(out) = graph.find_output_modules().first() {
-      output = translation.len();
+      let output = translation.len();
       translation.push(*out);
       edges[backwards[exit].unwrap()] = vec![Some(Edge{target: output, input_num: 0})];
       edges.push(vec![]);
     }
-    Subgraph{graph, exit: output, translation, edges}
+    Subgraph{graph, translation, edges}
   }
 
+  /// Push the button until the states repeat, recording the pushes that send a high
+  /// output. The cycle's prefix counts the state before the first push.
   fn find_cycle(&self) -> CycleTracker {
-    let mut states: HashMap<Vec<State>, usize> = HashMap::new();
+    let mut flow = FlowState::new(self);
     let mut time = 0;
-    let mut state = FlowState::new(self);
     let mut outputs = Vec::new();
-    let start;
-    loop {
-      state.outputs[MessageKind::High as usize] = 0;
-      let prev_state = state.states.clone();
-      state.push_button(false);
+    let start = flow.states.clone();
+    let cycle = find_cycle(start, |states| {
+      flow.outputs[MessageKind::High as usize] = 0;
+      let prev_state = states.clone();
+      flow.push_button_on(states);
       time += 1;
-      if let Some(prev) = states.get(&state.states) {
-        start = *prev;
-        break;
-      }
-      states.insert(state.states.clone(), time);
-      if state.outputs[MessageKind::High as usize] > 0 {
+      if flow.outputs[MessageKind::High as usize] > 0 {
         outputs.push(OutputMatch {time, prev_state});
       }
-    }
-    CycleTracker{outputs, start, length: time - start}
+    }, Memory::States);
+    CycleTracker{outputs, cycle}
   }
 }
 
@@ -432,10 +432,6 @@ impl<'a> Graph for Subgraph<'a> {
     &self.edges[node]
   }
 
-  fn is_exit(&self, node: usize) -> bool {
-    node == self.exit
-  }
-
   fn num_nodes(&self) -> usize {
     self.translation.len()
   }
@@ -512,10 +508,8 @@ struct OutputMatch {
 struct CycleTracker {
   // The messages that match our output criteria
   outputs: Vec<OutputMatch>,
-  // The start of the first cycle
-  start: usize,
-  // the length of the cycle
-  length: usize,
+  // When the states start to repeat and how often
+  cycle: Cycle,
 }
 
 impl CycleTracker {
@@ -553,21 +547,23 @@ impl CycleTracker {
 
   fn solve(parts: &[Subgraph], cycles: &[CycleTracker]) -> Option<usize> {
     // I don't think it is possible to generate an input with a cycle other than at the start.
-    if cycles.iter().any(|c| c.start != 1) {
+    // The state before the first push may be left out of the cycle, as long as the state
+    // after it is in the cycle, since the presses are counted from 1.
+    if cycles.iter().any(|c| c.cycle.prefix > 1) {
       return None;
     }
     // Generate all combinations of the cycles and find the best.
     cycles.iter().map(|cycle| cycle.outputs.iter()
-        .map(|om| (&om.prev_state, Recurrence{cycle: cycle.length,
-              remainder: om.time % cycle.length})))
+        .map(|om| (&om.prev_state, Recurrence{cycle: cycle.cycle.period,
+              remainder: om.time % cycle.cycle.period})))
         .multi_cartesian_product()
         .filter_map(|cp | Self::solve_one(parts, &cp))
         .min()
   }
 }
 
-pub fn generator(input: &str) -> Configuration {
-  Configuration::from_str(input).unwrap()
+pub fn generator(input: &str) -> Result<Configuration, ParseError> {
+  Configuration::from_str(input)
 }
 
 pub fn part1(input: &Configuration) -> usize {
@@ -598,33 +594,40 @@ pub fn part2(input: &Configuration) -> usize {
   brute_force_part2(input)
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day20::{generator, part1, part2, Recurrence};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "broadcaster -> a, b, c
 %a -> b
 %b -> c
 %c -> inv
 &inv -> a";
 
-  const INPUT2: &str =
+const EXAMPLE2: &str =
 "broadcaster -> a
 %a -> inv, con
 &inv -> b
 %b -> con
 &con -> rx";
 
+pub const INFO: DayInfo = DayInfo{title: "Pulse Propagation", year: 2023, day: 20, examples: &[
+  Example{input: EXAMPLE, part1: Some("32000000"), part2: None},
+  Example{input: EXAMPLE2, part1: Some("11687500"), part2: Some("1")}],
+  tags: &["graph"]};
+
+crate::solution!(Configuration);
+
+#[cfg(test)]
+mod tests {
+  use crate::day20::{generator, part1, part2, Recurrence, EXAMPLE, EXAMPLE2};
+
   #[test]
   fn test_part1() {
-    assert_eq!(32000000, part1(&generator(INPUT)));
-    assert_eq!(11687500, part1(&generator(INPUT2)));
+    assert_eq!(32000000, part1(&generator(EXAMPLE).unwrap()));
+    assert_eq!(11687500, part1(&generator(EXAMPLE2).unwrap()));
   }
 
   #[test]
   fn test_part2() {
-    assert_eq!(1, part2(&generator(INPUT2)));
+    assert_eq!(1, part2(&generator(EXAMPLE2).unwrap()));
   }
 
   #[test]
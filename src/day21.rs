@@ -1,9 +1,9 @@
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
-use std::ops;
-use std::ops::Range;
 use num_integer::Integer;
 use smallvec::SmallVec;
+use crate::utils::{DayInfo, Dir4, Example, Grid, ParseError, Point};
+use crate::utils::search::bfs_distances;
 
 #[derive(Clone,Copy,Debug,Eq,PartialEq)]
 pub enum Spot {
@@ -12,8 +12,10 @@ pub enum Spot {
   Start,
 }
 
-impl Spot {
-  fn from_char(ch: char) -> Result<Spot,String> {
+impl TryFrom<char> for Spot {
+  type Error = String;
+
+  fn try_from(ch: char) -> Result<Spot,String> {
     match ch {
       'S' => Ok(Spot::Start),
       '.' => Ok(Spot::Garden),
@@ -26,19 +28,7 @@ impl Spot {
 type Position = i32;
 type Time = u32;
 
-#[derive(Clone,Copy,Debug,Eq,Hash,Ord,PartialEq,PartialOrd)]
-pub struct Coordinate {
-  x: Position,
-  y: Position,
-}
-
-impl ops::Add for Coordinate {
-  type Output = Coordinate;
-
-  fn add(self, rhs: Coordinate) -> Self::Output {
-    Coordinate {x: self.x + rhs.x, y: self.y + rhs.y}
-  }
-}
+type Coordinate = Point<Position>;
 
 #[derive(Clone,Copy,Debug,Eq,Hash,PartialEq)]
 struct GridCoordinate {
@@ -105,14 +95,7 @@ impl Repetition {
   }
 }
 
-#[derive(Clone,Copy,Debug)]
-enum Direction {
-  North,
-  West,
-  South,
-  East,
-}
-
+/// Looks for the repetitions in each direction from the starting grid, indexed by Dir4.
 #[derive(Clone,Debug,Default)]
 struct RepetitionFinder {
   directions: [Repetition; 4],
@@ -121,14 +104,14 @@ struct RepetitionFinder {
 }
 
 impl RepetitionFinder {
-  fn find_direction(grid: GridCoordinate) -> Option<(Direction,Position)> {
+  fn find_direction(grid: GridCoordinate) -> Option<(Dir4,Position)> {
     match grid.x.cmp(&0) {
-      Ordering::Less => Some((Direction::West, grid.x)),
-      Ordering::Greater => Some((Direction::East, grid.x)),
+      Ordering::Less => Some((Dir4::West, grid.x)),
+      Ordering::Greater => Some((Dir4::East, grid.x)),
       Ordering::Equal => match grid.y.cmp(&0) {
-        Ordering::Less => Some((Direction::North, grid.y)),
+        Ordering::Less => Some((Dir4::North, grid.y)),
         Ordering::Equal => None,
-        Ordering::Greater => Some((Direction::South, grid.y)),
+        Ordering::Greater => Some((Dir4::South, grid.y)),
       }
     }
   }
@@ -143,13 +126,17 @@ impl RepetitionFinder {
     }
   }
 
+  fn get(&self, dir: Dir4) -> &Repetition {
+    &self.directions[dir as usize]
+  }
+
   fn is_done(&self) -> bool {
     self.done.iter().all(|&x| x)
   }
 
   fn is_unique(&self, grid: GridCoordinate) -> bool {
-    (self.directions[0].start..=self.directions[2].start).contains(&grid.y) &&
-        (self.directions[1].start..=self.directions[3].start).contains(&grid.x)
+    (self.get(Dir4::North).start..=self.get(Dir4::South).start).contains(&grid.y) &&
+        (self.get(Dir4::West).start..=self.get(Dir4::East).start).contains(&grid.x)
   }
 
   fn count_corner(time: Time, stride1: Time, stride2: Time, summary: &GridSummary) -> usize {
@@ -214,95 +201,88 @@ impl RepetitionFinder {
     if !self.is_done() {
       return summaries.values().map(|s| s.count_squares(time)).sum()
     }
+    let (north, west) = (self.get(Dir4::North), self.get(Dir4::West));
+    let (south, east) = (self.get(Dir4::South), self.get(Dir4::East));
     // Get the counts for the uniques
     let mut result: usize = summaries.iter()
         .filter(|(&grid, _)| self.is_unique(grid))
         .map(|(_, summary)| summary.count_squares(time)).sum();
     // West edge
-    result += Self::count_stripe(time, self.directions[1].stride,
-                                 &(self.directions[0].start..=self.directions[2].start).into_iter()
-                                     .map(|y| &summaries[&GridCoordinate{x:self.directions[1].start, y}])
+    result += Self::count_stripe(time, west.stride,
+                                 &(north.start..=south.start)
+                                     .map(|y| &summaries[&GridCoordinate{x:west.start, y}])
                                      .collect());
     // East edge
-    result += Self::count_stripe(time, self.directions[3].stride,
-                                 &(self.directions[0].start..=self.directions[2].start).into_iter()
-                                     .map(|y| &summaries[&GridCoordinate{x:self.directions[3].start, y}])
+    result += Self::count_stripe(time, east.stride,
+                                 &(north.start..=south.start)
+                                     .map(|y| &summaries[&GridCoordinate{x:east.start, y}])
                                      .collect());
     // North edge
-    result += Self::count_stripe(time, self.directions[0].stride,
-                                 &(self.directions[1].start..=self.directions[3].start).into_iter()
-                                     .map(|x| &summaries[&GridCoordinate{x, y:self.directions[0].start}])
+    result += Self::count_stripe(time, north.stride,
+                                 &(west.start..=east.start)
+                                     .map(|x| &summaries[&GridCoordinate{x, y:north.start}])
                                      .collect());
     // South edge
-    result += Self::count_stripe(time, self.directions[2].stride,
-                                 &(self.directions[1].start..=self.directions[3].start).into_iter()
-                                     .map(|x| &summaries[&GridCoordinate{x, y:self.directions[2].start}])
+    result += Self::count_stripe(time, south.stride,
+                                 &(west.start..=east.start)
+                                     .map(|x| &summaries[&GridCoordinate{x, y:south.start}])
                                      .collect());
-    // North East corner
-    result += Self::count_corner(time, self.directions[0].stride, self.directions[1].stride,
-                                 &summaries[&GridCoordinate{x: self.directions[1].start,
-                                   y: self.directions[0].start}]);
     // North West corner
-    result += Self::count_corner(time, self.directions[0].stride, self.directions[3].stride,
-                                 &summaries[&GridCoordinate{x: self.directions[3].start,
-                                   y: self.directions[0].start}]);
-    // South East corner
-    result += Self::count_corner(time, self.directions[2].stride, self.directions[1].stride,
-                                 &summaries[&GridCoordinate{x: self.directions[1].start,
-                                   y: self.directions[2].start}]);
+    result += Self::count_corner(time, north.stride, west.stride,
+                                 &summaries[&GridCoordinate{x: west.start, y: north.start}]);
+    // North East corner
+    result += Self::count_corner(time, north.stride, east.stride,
+                                 &summaries[&GridCoordinate{x: east.start, y: north.start}]);
     // South West corner
-    result += Self::count_corner(time, self.directions[2].stride, self.directions[3].stride,
-                                 &summaries[&GridCoordinate{x: self.directions[3].start,
-                                   y: self.directions[2].start}]);
+    result += Self::count_corner(time, south.stride, west.stride,
+                                 &summaries[&GridCoordinate{x: west.start, y: south.start}]);
+    // South East corner
+    result += Self::count_corner(time, south.stride, east.stride,
+                                 &summaries[&GridCoordinate{x: east.start, y: south.start}]);
     result
   }
 }
 
 #[derive(Clone,Debug)]
 pub struct Map {
-  spots: Vec<Vec<Spot>>,
+  spots: Grid<Spot>,
   start: Coordinate,
-  width: Range<Position>,
-  height: Range<Position>,
 }
 
 impl Map {
-  fn from_str(s: &str) -> Result<Self,String> {
-    let mut start : Option<Coordinate> = None;
-    let spots = s.lines().enumerate()
-        .map(|(y, line)| line.chars().enumerate()
-            .map(|(x, ch)| {
-              let s = Spot::from_char(ch);
-              if let Ok(Spot::Start) = s {
-                start = Some(Coordinate {x: x as i32, y: y as i32})
-              }
-              s })
-            .collect::<Result<Vec<Spot>,String>>())
-        .collect::<Result<Vec<Vec<Spot>>,String>>()?;
-    let start = start.ok_or("No start".to_string())?;
-    let width = 0..(spots[0].len() as Position);
-    let height = 0..(spots.len() as Position);
-    Ok(Map{spots, start, width, height})
+  fn from_str(s: &str) -> Result<Self,ParseError> {
+    let spots = Grid::parse(s)?;
+    let start = spots.position(|&s| s == Spot::Start)
+        .map(|(x, y)| Coordinate::new(x as Position, y as Position))
+        .ok_or_else(|| ParseError::new(s, "No start"))?;
+    Ok(Map{spots, start})
+  }
+
+  fn width(&self) -> Position {
+    self.spots.width() as Position
+  }
+
+  fn height(&self) -> Position {
+    self.spots.height() as Position
   }
 
   fn contains(&self, location: Coordinate) -> bool {
-    self.width.contains(&location.x) && self.height.contains(&location.y)
+    self.spots.contains(location.x, location.y)
   }
 
+  /// Get the spot, where the map repeats forever in every direction.
   fn get_spot(&self, location: Coordinate) -> Spot {
-    self.spots[location.y.rem_euclid(self.height.end) as usize]
-        [location.x.rem_euclid(self.width.end) as usize]
+    *self.spots.get_wrapped(location.x, location.y)
   }
 
   fn convert_to_grid(&self, location: Coordinate) -> GridCoordinate {
-    GridCoordinate {x: location.x.div_euclid(self.width.end), y: location.y.div_euclid(self.height.end)}
+    GridCoordinate {x: location.x.div_euclid(self.width()), y: location.y.div_euclid(self.height())}
   }
 
   fn next<const LIMITLESS: bool>(&self, spot: Coordinate) -> SmallVec<[Coordinate;4]> {
     let mut result = SmallVec::new();
-    for dir in [Coordinate {x:0, y:-1}, Coordinate {x:-1, y:0},
-                Coordinate {x:0, y:1}, Coordinate {x:1, y:0}] {
-      let new = spot + dir;
+    for dir in Dir4::ALL {
+      let new = spot.step(dir, 1);
       if (LIMITLESS || self.contains(new)) && self.get_spot(new) != Spot::Rock {
         result.push(new);
       }
@@ -325,15 +305,15 @@ impl Map {
       top = top.min(c.y);
       bottom = bottom.max(c.y);
     }
-    Some((Coordinate{x: left, y: top}, Coordinate{x: right, y: bottom}))
+    Some((Coordinate::new(left, top), Coordinate::new(right, bottom)))
   }
 
   pub fn print(&self, done: &HashSet<Coordinate>) {
     if let Some((left_top, right_bottom)) = Self::bounds(done) {
       for y in left_top.y-1..=right_bottom.y+1 {
-        if y.rem_euclid(self.height.end) == 0 {
+        if y.rem_euclid(self.height()) == 0 {
           for x in left_top.x-1..=right_bottom.x+1 {
-            if x.rem_euclid(self.width.end) == 0 {
+            if x.rem_euclid(self.width()) == 0 {
               print!("+");
             }
             print!("-");
@@ -341,10 +321,10 @@ impl Map {
           println!();
         }
         for x in left_top.x-1..=right_bottom.x+1 {
-          if x.rem_euclid(self.width.end) == 0 {
+          if x.rem_euclid(self.width()) == 0 {
             print!("|");
           }
-          let coord = Coordinate{x, y};
+          let coord = Coordinate::new(x, y);
           if done.contains(&coord) {
             print!("O");
           } else if self.get_spot(coord) == Spot::Rock {
@@ -358,32 +338,20 @@ impl Map {
     }
   }
 
+  /// Count the spots that can be reached in exactly dist steps. Since the
+  /// steps can go back and forth, those are the spots that are at most dist
+  /// away and at an even distance from it.
   fn moves<const LIMITLESS: bool>(&self, dist: Time) -> usize {
-    let mut frontier : HashSet<Coordinate> = HashSet::new();
-    let mut done = [(); 2].map(|_| HashSet::new());
-    frontier.insert(self.start.clone());
-    done[0].insert(self.start.clone());
-    for t in 1..=dist {
-      let mut next = HashSet::new();
-      for loc in frontier.into_iter() {
-        for n in self.next::<LIMITLESS>(loc) {
-          if done[t as usize % 2].insert(n) {
-            next.insert(n);
-          }
-        }
-      }
-      frontier = next;
-    }
-    //self.print(&done[(dist as usize) % 2]);
-    //println!();
-    done[(dist as usize) % 2].len()
+    bfs_distances(self.start, |&loc| self.next::<LIMITLESS>(loc), dist as usize).values()
+        .filter(|&&steps| steps % 2 == dist as usize % 2)
+        .count()
   }
 
   fn unbounded_moves(&self, dist: Time) -> usize {
     let mut repetitions = RepetitionFinder::default();
     let mut frontier : HashSet<Coordinate> = HashSet::new();
     let mut summaries = HashMap::new();
-    frontier.insert(self.start.clone());
+    frontier.insert(self.start);
     let mut init_summary = GridSummary::init(0);
     init_summary.add_count(1);
     summaries.insert(GridCoordinate {x:0, y:0}, init_summary);
@@ -428,9 +396,8 @@ impl Map {
   }
 }
 
-pub fn generator(input: &str) -> Map {
+pub fn generator(input: &str) -> Result<Map, ParseError> {
   Map::from_str(input)
-    .unwrap() // panics on error
 }
 
 pub fn part1(input: &Map) -> usize {
@@ -441,11 +408,7 @@ pub fn part2(input: &Map) -> usize {
   input.unbounded_moves(26_501_365)
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day21::{generator};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "...........
 .....###.#.
 .###.##..#.
@@ -458,9 +421,19 @@ mod tests {
 .##..##.##.
 ...........";
 
+pub const INFO: DayInfo = DayInfo{title: "Step Counter", year: 2023, day: 21, examples: &[
+  Example{input: EXAMPLE, part1: None, part2: None}],
+  tags: &["grid"]};
+
+crate::solution!(Map);
+
+#[cfg(test)]
+mod tests {
+  use crate::day21::{generator, EXAMPLE};
+
   #[test]
   fn test_part1() {
-    let input = generator(INPUT);
+    let input = generator(EXAMPLE).unwrap();
     assert_eq!(1, input.moves::<false>(0));
     assert_eq!(2, input.moves::<false>(1));
     assert_eq!(4, input.moves::<false>(2));
@@ -469,7 +442,7 @@ mod tests {
 
   #[test]
   fn test_part2() {
-    let input = generator(INPUT);
+    let input = generator(EXAMPLE).unwrap();
     assert_eq!(1, input.unbounded_moves(0));
     assert_eq!(2, input.unbounded_moves(1));
     assert_eq!(4, input.unbounded_moves(2));
@@ -1,43 +1,38 @@
-use std::cmp;
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::ops::Range;
 use array2d::Array2D;
+use crate::utils::{DayInfo, Example, ParseError, Point3};
 
 type Position = i32;
 
+/// A block, given by the corners with the smallest and largest coordinates.
+/// Both corners are part of the block.
 #[derive(Clone,Debug,Eq,PartialEq)]
 pub struct Block {
-  z: Range<Position>,
-  x: Range<Position>,
-  y: Range<Position>,
+  low: Point3<Position>,
+  high: Point3<Position>,
 }
 
 impl Block {
-  fn parse_coordinate(word: &str) -> Result<Vec<Position>,String> {
-    word.split(',')
-        .map(|w| w.trim().parse::<Position>()
-            .map_err(|_| format!("Can't parse {w}")))
-        .collect::<Result<Vec<Position>,String>>()
+  fn from_str(line: &str) -> Result<Self,ParseError> {
+    let (left,right) = line.split_once('~')
+        .ok_or_else(|| ParseError::new(line, "Can't find ~"))?;
+    let left = Point3::parse(left)?;
+    let right = Point3::parse(right)?;
+    Ok(Block{low: left.min(right), high: left.max(right)})
   }
 
-  fn make_range(p1: Position, p2: Position) -> Range<Position> {
-    let left = p1.min(p2);
-    let right = p1.max(p2);
-    left..(right+1)
+  fn xs(&self) -> Range<Position> {
+    self.low.x..self.high.x + 1
   }
 
-  fn from_str(line: &str) -> Result<Self,String> {
-    let (left,right) = line.split_once('~').ok_or("Can't find ~")?;
-    let left_parts = Self::parse_coordinate(left)?;
-    let right_parts = Self::parse_coordinate(right)?;
-    if left_parts.len() != 3 || right_parts.len() != 3 {
-      return Err("Need three positions".to_string());
-    }
-    let ranges = left_parts.iter().zip(right_parts.iter())
-        .map(|(l, r) | Self::make_range(*l, *r))
-        .collect::<Vec<Range<Position>>>();
-    Ok(Block{z: ranges[2].clone(), x: ranges[0].clone(), y: ranges[1].clone()})
+  fn ys(&self) -> Range<Position> {
+    self.low.y..self.high.y + 1
+  }
+
+  fn height(&self) -> usize {
+    (self.high.z - self.low.z + 1) as usize
   }
 }
 
@@ -48,22 +43,21 @@ impl PartialOrd<Self> for Block {
 }
 
 impl Ord for Block {
-  fn cmp(&self, other: &Self) -> cmp::Ordering {
-    self.z.start.cmp(&other.z.start)
-        .then_with(|| self.x.start.cmp(&other.x.start))
-        .then_with(|| self.y.start.cmp(&other.y.start))
-        .then_with(|| self.z.end.cmp(&other.z.end))
-        .then_with(|| self.x.end.cmp(&other.x.end))
-        .then_with(|| self.y.end.cmp(&other.y.end))
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.low.z.cmp(&other.low.z)
+        .then_with(|| self.low.x.cmp(&other.low.x))
+        .then_with(|| self.low.y.cmp(&other.low.y))
+        .then_with(|| self.high.z.cmp(&other.high.z))
+        .then_with(|| self.high.x.cmp(&other.high.x))
+        .then_with(|| self.high.y.cmp(&other.high.y))
   }
 }
 
-pub fn generator(input: &str) -> Vec<Block> {
-  let mut result = input.lines().enumerate()
-      .map(|(i, l)| Block::from_str(l).map_err(|e| format!("line {i}: {e}")))
-      .collect::<Result<Vec<Block>,String>>().unwrap();
+pub fn generator(input: &str) -> Result<Vec<Block>, ParseError> {
+  let mut result = input.lines().map(Block::from_str)
+      .collect::<Result<Vec<Block>,ParseError>>()?;
   result.sort_unstable();
-  result
+  Ok(result)
 }
 
 #[derive(Clone,Default,Debug)]
@@ -82,30 +76,26 @@ struct Surface {
 impl Surface {
   fn init(input: &[Block]) -> Self {
     let first = input.first().unwrap();
-    let mut x_range = first.x.clone();
-    let mut y_range = first.y.clone();
-    for blk in input[1..].iter() {
-      x_range.start = x_range.start.min(blk.x.start);
-      x_range.end = x_range.end.max(blk.x.end);
-      y_range.start = y_range.start.min(blk.y.start);
-      y_range.end = y_range.end.max(blk.y.end);
-    }
+    let low = input.iter().fold(first.low, |low, blk| low.min(blk.low));
+    let high = input.iter().fold(first.high, |high, blk| high.max(blk.high));
+    let x_range = low.x..high.x + 1;
+    let y_range = low.y..high.y + 1;
     let surface = Array2D::filled_with(Cell::default(), x_range.len(), y_range.len());
     Surface{x_range, y_range, surface}
   }
 
   fn update(&mut self, blk_id: usize, blk: &Block) -> Vec<usize> {
     let mut height = 0;
-    for x in blk.x.clone() {
-      for y in blk.y.clone() {
+    for x in blk.xs() {
+      for y in blk.ys() {
         let cell = self.surface.get((x - self.x_range.start) as usize,
                                     (y - self.y_range.start) as usize).unwrap();
         height = height.max(cell.height);
       }
     }
     let mut support = Vec::new();
-    for x in blk.x.clone() {
-      for y in blk.y.clone() {
+    for x in blk.xs() {
+      for y in blk.ys() {
         let cell = self.surface.get_mut((x - self.x_range.start) as usize,
                                     (y - self.y_range.start) as usize).unwrap();
         if height == cell.height {
@@ -115,7 +105,7 @@ impl Surface {
             }
           }
         }
-        cell.height = height + blk.z.len();
+        cell.height = height + blk.height();
         cell.block = Some(blk_id);
       }
     }
@@ -170,11 +160,7 @@ pub fn part2(input: &[Block]) -> usize {
   supported_by.values().map(|l| l.len()).sum()
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day22::{generator,part1,part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "1,0,1~1,2,1
 0,0,2~2,0,2
 0,2,3~2,2,3
@@ -183,15 +169,25 @@ mod tests {
 0,1,6~2,1,6
 1,1,8~1,1,9";
 
+pub const INFO: DayInfo = DayInfo{title: "Sand Slabs", year: 2023, day: 22, examples: &[
+  Example{input: EXAMPLE, part1: Some("5"), part2: Some("7")}],
+  tags: &[]};
+
+crate::solution!(Vec<Block>);
+
+#[cfg(test)]
+mod tests {
+  use crate::day22::{generator,part1,part2, EXAMPLE};
+
   #[test]
   fn test_part1() {
-    let input = generator(INPUT);
+    let input = generator(EXAMPLE).unwrap();
     assert_eq!(5, part1(&input));
   }
 
   #[test]
   fn test_part2() {
-    let input = generator(INPUT);
+    let input = generator(EXAMPLE).unwrap();
     assert_eq!(7, part2(&input));
   }
 }
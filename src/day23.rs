@@ -1,88 +1,158 @@
-#[derive(Clone,Copy,Debug,Eq,PartialEq)]
-pub enum Direction {
-  North,
-  West,
-  South,
-  East,
-}
+use std::collections::HashMap;
+use crate::utils::{DayInfo, Dir4, Example, Grid, ParseError, Point};
+use crate::utils::search::bfs;
 
 #[derive(Clone,Copy,Debug,Eq,PartialEq)]
 pub enum FloorType {
   Space,
   Forrest,
-  Slope(Direction),
+  Slope(Dir4),
 }
 
-impl FloorType {
-  fn from_char(ch: char) -> Result<Self,String> {
+impl TryFrom<char> for FloorType {
+  type Error = String;
+
+  fn try_from(ch: char) -> Result<Self,String> {
     Ok(match ch {
       '.' => Self::Space,
       '#' => Self::Forrest,
-      '>' => Self::Slope(Direction::East),
-      '<' => Self::Slope(Direction::West),
-      '^' => Self::Slope(Direction::North),
-      'v' => Self::Slope(Direction::South),
+      '>' => Self::Slope(Dir4::East),
+      '<' => Self::Slope(Dir4::West),
+      '^' => Self::Slope(Dir4::North),
+      'v' => Self::Slope(Dir4::South),
       _ => return Err(format!("Can't understand '{ch}'")),
     })
   }
 }
 
-type Position = u32;
-
-#[derive(Clone,Debug,Eq,PartialEq)]
-struct Coordinate {
-  x: Position,
-  y: Position,
-}
+type Position = i32;
+type Coordinate = Point<Position>;
 
 #[derive(Debug)]
 pub struct Map {
   start: Coordinate,
   end: Coordinate,
-  bounds: Coordinate,
-  floor: Vec<Vec<FloorType>>,
+  floor: Grid<FloorType>,
 }
 
 impl Map {
-  fn from_str(input: &str) -> Result<Self,String> {
-    let floor: Vec<Vec<FloorType>> = input.lines()
-        .map(|l| l.chars().map(FloorType::from_char)
-            .collect::<Result<Vec<FloorType>,String>>())
-        .collect::<Result<Vec<Vec<FloorType>>,String>>()?;
-    if floor.is_empty() {
-      return Err("Empty input".to_string());
+  fn from_str(input: &str) -> Result<Self,ParseError> {
+    let floor: Grid<FloorType> = Grid::parse(input)?;
+    let lines = input.lines().collect::<Vec<&str>>();
+    let start = Coordinate::new(floor.row(0).iter().position(|x| *x == FloorType::Space)
+        .ok_or_else(|| ParseError::new(lines[0], "No start in the first row"))? as Position, 0);
+    let end = Coordinate::new(floor.row(floor.height() - 1).iter()
+        .position(|s| *s == FloorType::Space)
+        .ok_or_else(|| ParseError::new(lines[lines.len() - 1], "No end in the last row"))?
+        as Position, floor.height() as Position - 1);
+    Ok(Map{start, end, floor})
+  }
+
+  /// Get the floor at the spot, where everything off the map is forest.
+  fn get(&self, spot: &Coordinate) -> FloorType {
+    self.floor.get(spot.x, spot.y).copied().unwrap_or(FloorType::Forrest)
+  }
+
+  /// Find the open neighbors of a spot. If the map is slippery, we can only
+  /// leave a slope in its direction.
+  fn neighbors<const SLIPPERY: bool>(&self, spot: &Coordinate) -> Vec<Coordinate> {
+    let dirs: &[Dir4] = match self.get(spot) {
+      FloorType::Slope(dir) if SLIPPERY => &[dir],
+      _ => &Dir4::ALL,
+    };
+    dirs.iter()
+        .map(|&d| spot.step(d, 1))
+        .filter(|n| self.get(n) != FloorType::Forrest)
+        .collect()
+  }
+
+  /// A junction is a spot with at least three open neighbors or the start or end.
+  fn is_junction(&self, spot: &Coordinate) -> bool {
+    *spot == self.start || *spot == self.end ||
+        (self.get(spot) != FloorType::Forrest && self.neighbors::<false>(spot).len() > 2)
+  }
+
+  /// Compress the map down to a graph between the junctions, where each
+  /// edge is the length of the corridor between them.
+  fn build_graph<const SLIPPERY: bool>(&self) -> Graph {
+    let junctions: Vec<Coordinate> = self.floor.iter()
+        .map(|((x, y), _)| Coordinate::new(x as Position, y as Position))
+        .filter(|c| self.is_junction(c))
+        .collect();
+    let index: HashMap<Coordinate, usize> = junctions.iter().enumerate()
+        .map(|(i, c)| (*c, i)).collect();
+    let mut edges = vec![Vec::new(); junctions.len()];
+    for (from, junction) in junctions.iter().enumerate() {
+      for first in self.neighbors::<SLIPPERY>(junction) {
+        // Walk down the corridor until we reach the next junction.
+        let corridor = |spot: &Coordinate| self.neighbors::<SLIPPERY>(spot).into_iter()
+            .filter(|n| n != junction);
+        if let Some(found) = bfs(first, corridor, |spot| index.contains_key(spot)) {
+          edges[from].push(Edge{target: index[&found.goal], distance: found.cost + 1});
+        }
+      }
+    }
+    Graph{start: index[&self.start], end: index[&self.end], edges}
+  }
+}
+
+#[derive(Clone,Debug)]
+struct Edge {
+  target: usize,
+  distance: usize,
+}
+
+/// The junctions of the map and the corridors between them.
+#[derive(Debug)]
+struct Graph {
+  start: usize,
+  end: usize,
+  edges: Vec<Vec<Edge>>,
+}
+
+impl Graph {
+  /// Find the longest path from start to end that doesn't revisit a junction.
+  fn longest_path(&self) -> Option<usize> {
+    assert!(self.edges.len() <= u64::BITS as usize, "Too many junctions");
+    // The end is a dead end, so once we reach the junction next to it, we must
+    // go to the end or we'll never get there.
+    let (goal, extra) = match &self.edges.iter().enumerate()
+        .flat_map(|(from, e)| e.iter()
+            .filter(|e| e.target == self.end)
+            .map(move |e| (from, e.distance)))
+        .collect::<Vec<(usize, usize)>>()[..] {
+      [(from, distance)] => (*from, *distance),
+      _ => (self.end, 0),
+    };
+    self.search(self.start, goal, 1 << self.start)
+        .map(|d| d + extra)
+  }
+
+  fn search(&self, node: usize, goal: usize, visited: u64) -> Option<usize> {
+    if node == goal {
+      return Some(0)
     }
-    let width = floor.iter().map(|x| x.len())
-        .max().unwrap();
-    let bounds = Coordinate{x : width as Position, y: floor.len() as Position};
-    let start = Coordinate{y: 0,
-      x: floor.first().unwrap().iter()
-          .position(|x| *x == FloorType::Space).unwrap() as Position};
-    let end = Coordinate{y: floor.len() as Position - 1,
-      x: floor.last().unwrap().iter()
-          .position(|s| *s == FloorType::Space).unwrap() as Position};
-    Ok(Map{start, end, floor, bounds})
+    self.edges[node].iter()
+        .filter(|e| visited & (1 << e.target) == 0)
+        .filter_map(|e| self.search(e.target, goal, visited | (1 << e.target))
+            .map(|d| d + e.distance))
+        .max()
   }
 }
 
-pub fn generator(input: &str) -> Map {
-  Map::from_str(input).expect("Problem parsing input")
+pub fn generator(input: &str) -> Result<Map, ParseError> {
+  Map::from_str(input)
 }
 
 pub fn part1(input: &Map) -> usize {
-  println!("{:?}", input);
-  0
+  input.build_graph::<true>().longest_path().expect("No path found")
 }
 
 pub fn part2(input: &Map) -> usize {
-  0
+  input.build_graph::<false>().longest_path().expect("No path found")
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day23::{generator,part1,part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "#.#####################
 #.......#########...###
 #######.#########.#.###
@@ -107,15 +177,25 @@ mod tests {
 #.....###...###...#...#
 #####################.#";
 
+pub const INFO: DayInfo = DayInfo{title: "A Long Walk", year: 2023, day: 23, examples: &[
+  Example{input: EXAMPLE, part1: Some("94"), part2: Some("154")}],
+  tags: &["grid", "graph"]};
+
+crate::solution!(Map);
+
+#[cfg(test)]
+mod tests {
+  use crate::day23::{generator,part1,part2, EXAMPLE};
+
   #[test]
   fn test_part1() {
-    let input = generator(INPUT);
-    assert_eq!(5, part1(&input));
+    let input = generator(EXAMPLE).unwrap();
+    assert_eq!(94, part1(&input));
   }
 
   #[test]
   fn test_part2() {
-    let input = generator(INPUT);
-    assert_eq!(7, part2(&input));
+    let input = generator(EXAMPLE).unwrap();
+    assert_eq!(154, part2(&input));
   }
 }
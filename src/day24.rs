@@ -0,0 +1,243 @@
+use std::ops::RangeInclusive;
+use itertools::Itertools;
+use crate::utils::{Answer, DayInfo, Example, ParseError, Point3};
+
+type Position = i64;
+
+/// Intermediate values (cross products of positions and velocities) need more room.
+type Wide = i128;
+
+type Vector = Point3<Wide>;
+
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Hailstone {
+  position: Point3<Position>,
+  velocity: Point3<Position>,
+}
+
+impl Hailstone {
+  fn from_str(line: &str) -> Result<Self,ParseError> {
+    let (position, velocity) = line.split_once('@')
+        .ok_or_else(|| ParseError::new(line, "Can't find @"))?;
+    Ok(Hailstone{position: Point3::parse(position)?, velocity: Point3::parse(velocity)?})
+  }
+
+  fn wide_position(&self) -> Vector {
+    self.position.map(|p| p as Wide)
+  }
+
+  fn wide_velocity(&self) -> Vector {
+    self.velocity.map(|v| v as Wide)
+  }
+
+  /// Where does this hailstone's x-y path cross the other's in the future?
+  /// The result is the crossing point scaled by the returned (positive) divisor,
+  /// so that everything stays in exact integer arithmetic.
+  fn future_crossing(&self, other: &Hailstone) -> Option<([Wide; 2], Wide)> {
+    let Point3{x: px, y: py, ..} = self.wide_position();
+    let Point3{x: vx, y: vy, ..} = self.wide_velocity();
+    let Point3{x: ox, y: oy, ..} = other.wide_position();
+    let Point3{x: ovx, y: ovy, ..} = other.wide_velocity();
+    // Solve px + t * vx = ox + s * ovx and py + t * vy = oy + s * ovy.
+    let mut det = ovx * vy - vx * ovy;
+    if det == 0 {
+      // parallel paths never cross
+      return None
+    }
+    let (dx, dy) = (ox - px, oy - py);
+    let mut t_num = ovx * dy - ovy * dx;
+    let mut s_num = vx * dy - vy * dx;
+    if det < 0 {
+      det = -det;
+      t_num = -t_num;
+      s_num = -s_num;
+    }
+    if t_num < 0 || s_num < 0 {
+      return None
+    }
+    Some(([px * det + t_num * vx, py * det + t_num * vy], det))
+  }
+}
+
+pub fn generator(input: &str) -> Result<Vec<Hailstone>, ParseError> {
+  input.lines()
+      .filter(|l| !l.trim().is_empty())
+      .map(Hailstone::from_str)
+      .collect()
+}
+
+fn count_crossings(input: &[Hailstone], area: RangeInclusive<Position>) -> usize {
+  let low = *area.start() as Wide;
+  let high = *area.end() as Wide;
+  let mut result = 0;
+  for (i, left) in input.iter().enumerate() {
+    for right in &input[i+1..] {
+      if let Some((point, divisor)) = left.future_crossing(right) {
+        if point.iter().all(|&p| low * divisor <= p && p <= high * divisor) {
+          result += 1;
+        }
+      }
+    }
+  }
+  result
+}
+
+const TEST_AREA: RangeInclusive<Position> = 200_000_000_000_000..=400_000_000_000_000;
+
+pub fn part1(input: &[Hailstone]) -> usize {
+  count_crossings(input, TEST_AREA)
+}
+
+/// Divide, but only if the result is an exact integer.
+fn exact_div(num: Wide, den: Wide) -> Option<Wide> {
+  if den != 0 && num % den == 0 {
+    Some(num / den)
+  } else {
+    None
+  }
+}
+
+/// In the frame of reference of the `base` hailstone, the rock must pass through
+/// the origin and the path of `target`, so it lies in the plane with `normal`.
+/// Find the time when `other` crosses that plane.
+fn plane_crossing_time(base: &Hailstone, normal: &Vector, other: &Hailstone) -> Option<Wide> {
+  let position = other.wide_position() - base.wide_position();
+  let velocity = other.wide_velocity() - base.wide_velocity();
+  exact_div(-normal.dot(position), normal.dot(velocity))
+}
+
+/// How many of the hailstones to pick the three from. Degenerate sets are rare, so
+/// this leaves plenty of sets to try without the search growing with the input.
+const CANDIDATES: usize = 8;
+
+/// Find the rock's starting position and velocity so that it hits every hailstone.
+/// Three hailstones are enough, unless they are degenerate, such as when two of them
+/// move in parallel, so try the sets of three from the first few until one works.
+fn find_rock(input: &[Hailstone]) -> Option<Hailstone> {
+  input.iter().take(CANDIDATES).tuple_combinations()
+      .find_map(|(base, first, second)| find_rock_from(base, first, second, input))
+}
+
+/// Find the rock from three of the hailstones using exact integer arithmetic and check
+/// that it hits all of them.
+fn find_rock_from(base: &Hailstone, first: &Hailstone, second: &Hailstone,
+                  input: &[Hailstone]) -> Option<Hailstone> {
+  let relative_normal = |h: &Hailstone| (h.wide_position() - base.wide_position())
+      .cross(h.wide_velocity() - base.wide_velocity());
+  let first_time = plane_crossing_time(base, &relative_normal(second), first)?;
+  let second_time = plane_crossing_time(base, &relative_normal(first), second)?;
+  let hit = |h: &Hailstone, t: Wide| h.wide_position() + h.wide_velocity() * t;
+  let first_hit = hit(first, first_time);
+  let second_hit = hit(second, second_time);
+  let delta = second_hit - first_hit;
+  let elapsed = second_time - first_time;
+  let velocity = Point3::new(exact_div(delta.x, elapsed)?, exact_div(delta.y, elapsed)?,
+                             exact_div(delta.z, elapsed)?);
+  let position = first_hit - velocity * first_time;
+  let narrow = |p: Vector| -> Option<Point3<Position>> {
+    Some(Point3::new(p.x.try_into().ok()?, p.y.try_into().ok()?, p.z.try_into().ok()?))
+  };
+  let rock = Hailstone{position: narrow(position)?, velocity: narrow(velocity)?};
+  // Make sure that the rock hits every hailstone.
+  input.iter().all(|h| hit_time(&rock, h).is_some()).then_some(rock)
+}
+
+/// Find the time when the rock hits the hailstone, which must be an integer time
+/// that isn't in the past.
+fn hit_time(rock: &Hailstone, hailstone: &Hailstone) -> Option<Wide> {
+  let gap = hailstone.wide_position() - rock.wide_position();
+  let closing = rock.wide_velocity() - hailstone.wide_velocity();
+  let mut time = None;
+  for (gap, closing) in [(gap.x, closing.x), (gap.y, closing.y), (gap.z, closing.z)] {
+    if closing == 0 {
+      // Moving together, they only meet if they are already level.
+      if gap != 0 {
+        return None
+      }
+      continue
+    }
+    let t = exact_div(gap, closing)?;
+    if time.is_some_and(|prev| prev != t) {
+      return None
+    }
+    time = Some(t);
+  }
+  // If they move together on every axis, they are at the same place at the start.
+  let time = time.unwrap_or(0);
+  (time >= 0).then_some(time)
+}
+
+/// The part is unsolved if no rock hits every hailstone.
+pub fn part2(input: &[Hailstone]) -> Answer {
+  find_rock(input).map_or(Answer::Unsolved,
+                          |rock| (rock.position.x + rock.position.y + rock.position.z).into())
+}
+
+const EXAMPLE: &str =
+"19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3";
+
+pub const INFO: DayInfo = DayInfo{title: "Never Tell Me The Odds", year: 2023, day: 24, examples: &[
+  // Part 1 uses a different test area for the example, so it can't be checked here.
+  Example{input: EXAMPLE, part1: None, part2: Some("47")}],
+  tags: &[]};
+
+crate::solution!(Vec<Hailstone>);
+
+#[cfg(test)]
+mod tests {
+  use crate::day24::{count_crossings, find_rock, find_rock_from, generator, hit_time, part2,
+                     Hailstone, EXAMPLE};
+  use crate::utils::{Answer, Point3};
+
+  #[test]
+  fn test_part1() {
+    assert_eq!(2, count_crossings(&generator(EXAMPLE).unwrap(), 7..=27));
+  }
+
+  #[test]
+  fn test_part2() {
+    let input = generator(EXAMPLE).unwrap();
+    let rock = find_rock(&input).unwrap();
+    assert_eq!(Point3::new(24, 13, 10), rock.position);
+    assert_eq!(Point3::new(-3, 1, 2), rock.velocity);
+    assert_eq!(Answer::from(47), part2(&input));
+  }
+
+  #[test]
+  fn test_no_rock() {
+    // Too few hailstones to pick three from.
+    let lines: Vec<&str> = EXAMPLE.lines().take(2).collect();
+    assert_eq!(Answer::Unsolved, part2(&generator(&lines.join("\n")).unwrap()));
+    // No straight line hits all of the hailstones.
+    let input = generator(&format!("{EXAMPLE}\n0, 0, 0 @ 1, 0, 0")).unwrap();
+    assert_eq!(Answer::Unsolved, part2(&input));
+  }
+
+  #[test]
+  fn test_degenerate_start() {
+    // The second hailstone moves in parallel with the first, so the first three can't
+    // find the rock.
+    let mut lines: Vec<&str> = EXAMPLE.lines().collect();
+    lines.insert(1, "22, 13, 18 @ -2, 1, -2");
+    let input = generator(&lines.join("\n")).unwrap();
+    assert_eq!(None, find_rock_from(&input[0], &input[1], &input[2], &input));
+    assert_eq!(Answer::from(47), part2(&input));
+  }
+  #[test]
+  fn test_hit_time() {
+    let stone = |line| Hailstone::from_str(line).unwrap();
+    let rock = stone("24, 13, 10 @ -3, 1, 2");
+    assert_eq!(Some(5), hit_time(&rock, &stone("19, 13, 30 @ -2, 1, -2")));
+    // Parallel, but apart.
+    assert_eq!(None, hit_time(&rock, &stone("25, 13, 10 @ -3, 1, 2")));
+    // The paths cross, but the rock got there first.
+    assert_eq!(None, hit_time(&rock, &stone("25, 13, 10 @ -2, 1, 2")));
+    // They would only meet half way through a step.
+    assert_eq!(None, hit_time(&rock, &stone("25, 13, 10 @ -5, 1, 2")));
+    assert_eq!(Some(0), hit_time(&rock, &rock));
+  }
+}
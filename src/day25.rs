@@ -1,4 +1,5 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use crate::utils::{Answer, DayInfo, Example, ParseError};
 
 type NodeId = u16;
 
@@ -14,8 +15,9 @@ pub struct Graph {
 }
 
 impl Graph {
-  fn parse_line(line: &str) -> Result<Vec<String>,String> {
-    let (left, right) = line.split_once(": ").ok_or("Can't parse {line}")?;
+  fn parse_line(line: &str) -> Result<Vec<String>,ParseError> {
+    let (left, right) = line.split_once(": ")
+        .ok_or_else(|| ParseError::new(line, "Can't find the ': ' separator"))?;
     let mut result = vec![left.to_string()];
     for next in right.split_whitespace() {
       result.push(next.to_string());
@@ -23,7 +25,7 @@ impl Graph {
     Ok(result)
   }
 
-  fn from_str(s: &str) -> Result<Self,String> {
+  fn from_str(s: &str) -> Result<Self,ParseError> {
     let mut names: HashMap<String, NodeId> = HashMap::new();
     let mut nodes = Vec::new();
     for line in s.lines() {
@@ -49,59 +51,114 @@ impl Graph {
     Ok(Graph{nodes,edges})
   }
 
-  fn is_partitioned(&self, removed: &[usize]) -> Option<(usize,usize)> {
-    let mut reachable = vec![false; self.nodes.len()];
-    reachable[0] = true;
-    let mut count = 1;
-    let mut changes = true;
-    while changes {
-      changes = false;
-      for (i, edge) in self.edges.iter().enumerate() {
-        if !removed.contains(&i) && reachable[edge.nodes[0] as usize] != reachable[edge.nodes[1] as usize] {
-          count += 1;
-          changes = true;
-          if reachable[edge.nodes[0] as usize] {
-            reachable[edge.nodes[1] as usize] = true;
-          } else {
-            reachable[edge.nodes[0] as usize] = true;
+  fn adjacency(&self) -> Vec<Vec<(usize, usize)>> {
+    let mut result = vec![Vec::new(); self.nodes.len()];
+    for (i, edge) in self.edges.iter().enumerate() {
+      result[edge.nodes[0] as usize].push((edge.nodes[1] as usize, i));
+      result[edge.nodes[1] as usize].push((edge.nodes[0] as usize, i));
+    }
+    result
+  }
+
+  /// Find the remaining capacity on the edge when moving away from the given node.
+  /// Each edge has capacity 1 in each direction and flow is positive in the
+  /// direction from the first node of the edge to the second.
+  fn residual(&self, edge: usize, from: usize, flow: &[i8]) -> i8 {
+    if self.edges[edge].nodes[0] as usize == from {
+      1 - flow[edge]
+    } else {
+      1 + flow[edge]
+    }
+  }
+
+  /// Compute the maximum flow from source to sink using Edmonds-Karp, but stop
+  /// once the flow reaches the limit. Returns the flow and which nodes are
+  /// still reachable from the source in the residual graph.
+  fn max_flow(&self, adjacency: &[Vec<(usize, usize)>], source: usize, sink: usize,
+              limit: usize) -> (usize, Vec<bool>) {
+    let mut flow = vec![0; self.edges.len()];
+    let mut total = 0;
+    loop {
+      // Breadth first search for a path with remaining capacity
+      let mut parent: Vec<Option<(usize, usize)>> = vec![None; self.nodes.len()];
+      let mut reachable = vec![false; self.nodes.len()];
+      reachable[source] = true;
+      let mut pending = VecDeque::from([source]);
+      while let Some(node) = pending.pop_front() {
+        if node == sink {
+          break
+        }
+        for &(next, edge) in &adjacency[node] {
+          if !reachable[next] && self.residual(edge, node, &flow) > 0 {
+            reachable[next] = true;
+            parent[next] = Some((node, edge));
+            pending.push_back(next);
           }
         }
       }
+      if !reachable[sink] || total >= limit {
+        return (total, reachable)
+      }
+      // Push one unit of flow back along the path
+      let mut node = sink;
+      while let Some((prev, edge)) = parent[node] {
+        flow[edge] += if self.edges[edge].nodes[0] as usize == prev { 1 } else { -1 };
+        node = prev;
+      }
+      total += 1;
     }
-    if count != reachable.len() {
-      Some((count, reachable.len() - count))
-    } else {
-      None
+  }
+
+  /// Find the global minimum cut by computing the max flow from the first node
+  /// to each of the other nodes.
+  pub fn min_cut(&self) -> Option<Cut> {
+    let adjacency = self.adjacency();
+    let mut best: Option<(usize, Vec<bool>)> = None;
+    for sink in 1..self.nodes.len() {
+      // We only care about flows that are smaller than the best so far.
+      let limit = best.as_ref().map(|(f, _)| *f).unwrap_or(usize::MAX);
+      let (flow, reachable) = self.max_flow(&adjacency, 0, sink, limit);
+      if flow < limit {
+        best = Some((flow, reachable));
+      }
     }
+    let (_, reachable) = best?;
+    let mut edges: Vec<(String, String)> = self.edges.iter()
+        .filter(|e| reachable[e.nodes[0] as usize] != reachable[e.nodes[1] as usize])
+        .map(|e| {
+          let mut pair = [&self.nodes[e.nodes[0] as usize], &self.nodes[e.nodes[1] as usize]];
+          pair.sort_unstable();
+          (pair[0].clone(), pair[1].clone())
+        })
+        .collect();
+    edges.sort_unstable();
+    let left = reachable.iter().filter(|&&r| r).count();
+    Some(Cut{edges, sizes: (left, self.nodes.len() - left)})
   }
 }
 
-pub fn generator(input: &str) -> Graph {
-  Graph::from_str(input).unwrap()
+/// The edges that were cut and the sizes of the two remaining components.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct Cut {
+  pub edges: Vec<(String, String)>,
+  pub sizes: (usize, usize),
 }
 
-pub fn part1(graph: &Graph) -> usize {
-  for e1 in 1..graph.edges.len() {
-    for e2 in e1+1..graph.edges.len() {
-      for e3 in e2+1..graph.edges.len() {
-        if let Some((part1, part2)) = graph.is_partitioned(&[e1, e2, e3]) {
-          return part1 * part2
-        }
-      }
-    }
-  }
-  0
+pub fn generator(input: &str) -> Result<Graph, ParseError> {
+  Graph::from_str(input)
 }
 
-pub fn part2(_input: &Graph) -> usize {
-  0
+pub fn part1(graph: &Graph) -> usize {
+  let cut = graph.min_cut().expect("Graph is too small to cut");
+  cut.sizes.0 * cut.sizes.1
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day25::{generator, part1};
+/// There is no puzzle for the second part of the last day.
+pub fn part2(_input: &Graph) -> Answer {
+  Answer::Unsolved
+}
 
-  const INPUT: &str =
+const EXAMPLE: &str =
 "jqt: rhn xhk nvd
 rsh: frs pzl lsr
 xhk: hfx
@@ -116,8 +173,28 @@ lsr: lhk
 rzs: qnr cmg lsr rsh
 frs: qnr lhk lsr";
 
+pub const INFO: DayInfo = DayInfo{title: "Snowverload", year: 2023, day: 25, examples: &[
+  Example{input: EXAMPLE, part1: Some("54"), part2: None}],
+  tags: &["graph"]};
+
+crate::solution!(Graph);
+
+#[cfg(test)]
+mod tests {
+  use crate::day25::{generator, part1, EXAMPLE};
+
   #[test]
   fn test_part1() {
-    assert_eq!(54, part1(&generator(INPUT)));
+    assert_eq!(54, part1(&generator(EXAMPLE).unwrap()));
+  }
+
+  #[test]
+  fn test_min_cut() {
+    let cut = generator(EXAMPLE).unwrap().min_cut().unwrap();
+    let names = |a: &str, b: &str| (a.to_string(), b.to_string());
+    assert_eq!(vec![names("bvb", "cmg"), names("hfx", "pzl"), names("jqt", "nvd")],
+               cut.edges);
+    assert_eq!(54, cut.sizes.0 * cut.sizes.1);
+    assert_eq!(15, cut.sizes.0 + cut.sizes.1);
   }
 }
@@ -5,26 +5,20 @@
 // * Process all of the numbers, determining their value and proximity
 //   to symbols.
 use smallvec::SmallVec;
+use crate::utils::{DayInfo, Example, Grid, ParseError};
 
 #[derive(Debug)]
 pub struct Board {
-  field: Vec<Vec<char>>,
-  width: usize,
-  height: usize,
+  field: Grid<char>,
 }
 
 impl Board {
-  fn from_str(s: &str) -> Self {
-    let field: Vec<Vec<char>> = s.lines()
-        .map(|l| l.chars().collect())
-        .collect();
-    let width = field[0].len();
-    let height = field.len();
-    Board{field, width, height}
+  fn from_str(s: &str) -> Result<Self, ParseError> {
+    Ok(Board{field: Grid::parse(s)?})
   }
 }
 
-pub fn generator(input: &str) -> Board {
+pub fn generator(input: &str) -> Result<Board, ParseError> {
   Board::from_str(input)
 }
 
@@ -40,19 +34,12 @@ trait NeighborTracker {
 fn find_neighbors<F>(result: &mut dyn NeighborTracker, board: &Board, filter: F)
     where F: Fn(char) -> bool {
   let mut next_id: usize = 0;
-  for (y, row) in board.field.iter().enumerate() {
-    for (x, spot) in row.iter().enumerate() {
-      if filter(*spot) {
-        let id = next_id;
-        next_id += 1;
-        for y_delta in -1i32..=1 {
-          for x_delta in -1i32..=1 {
-            if (0..board.height as i32).contains(&(y as i32 + y_delta)) &&
-                (0..board.width as i32).contains(&(x as i32 + x_delta)) {
-              result.mark((x as i32 + x_delta) as usize, (y as i32 + y_delta) as usize, id);
-            }
-          }
-        }
+  for ((x, y), spot) in board.field.iter() {
+    if filter(*spot) {
+      let id = next_id;
+      next_id += 1;
+      for (x, y) in board.field.neighbors8(x, y) {
+        result.mark(x, y, id);
       }
     }
   }
@@ -61,12 +48,12 @@ fn find_neighbors<F>(result: &mut dyn NeighborTracker, board: &Board, filter: F)
 /// For part 1, we track whether a given location is next to
 /// a symbol.
 struct SymbolNeighbors {
-  is_neighbor: Vec<Vec<bool>>,
+  is_neighbor: Grid<bool>,
 }
 
 impl NeighborTracker for SymbolNeighbors {
   fn mark(&mut self, x: usize, y: usize, _: usize) {
-    self.is_neighbor[y][x] = true;
+    self.is_neighbor[(x, y)] = true;
   }
 }
 
@@ -79,7 +66,7 @@ trait NumberProcessor {
 
 fn process_board(processor: &mut dyn NumberProcessor, board: &Board) {
   // Go through the board by row
-  for (y, row) in board.field.iter().enumerate() {
+  for (y, row) in board.field.rows().enumerate() {
     let mut in_number = false;
     for (x, spot) in row.iter().enumerate() {
       if spot.is_ascii_digit() {
@@ -108,7 +95,7 @@ struct PartCounter {
 impl NumberProcessor for PartCounter {
   fn add_digit(&mut self, digit: i32, x: usize, y: usize) {
     // for a string of digits, just one location has to be next to a symbol
-    self.include_current = self.include_current || self.symbol_neighbors.is_neighbor[y][x];
+    self.include_current = self.include_current || self.symbol_neighbors.is_neighbor[(x, y)];
     // keep the current value of the number
     self.current = self.current * 10 + digit;
   }
@@ -125,7 +112,8 @@ impl NumberProcessor for PartCounter {
 
 /// Find the sum of the numbers that are adjacent to symbols.
 pub fn part1(board: &Board) -> i32 {
-  let mut symbol_neighbors = SymbolNeighbors{is_neighbor: vec![vec![false; board.width]; board.height]};
+  let mut symbol_neighbors = SymbolNeighbors{
+    is_neighbor: Grid::filled(board.field.width(), board.field.height(), false)};
   find_neighbors(&mut symbol_neighbors, board, |ch| ch != '.' && ch.is_ascii_punctuation());
   let mut processor = PartCounter{symbol_neighbors, current: 0, include_current: false, result: 0};
   process_board(&mut processor, board);
@@ -137,14 +125,14 @@ pub fn part1(board: &Board) -> i32 {
 /// We also return the total number of gears found.
 #[derive(Debug)]
 struct GearMap {
-  neighbors: Vec<Vec<SmallVec<[usize; 2]>>>,
+  neighbors: Grid<SmallVec<[usize; 2]>>,
   gear_count: usize,
 }
 
 impl NeighborTracker for GearMap {
   fn mark(&mut self, x: usize, y: usize, id: usize) {
     self.gear_count = self.gear_count.max(id + 1);
-    self.neighbors[y][x].push(id);
+    self.neighbors[(x, y)].push(id);
   }
 }
 
@@ -161,7 +149,7 @@ struct GearCounter {
 impl NumberProcessor for GearCounter {
   fn add_digit(&mut self, digit: i32, x: usize, y: usize) {
     // Keep track of the set of all gears this number is next to.
-    for new_gear in &self.gear_map.neighbors[y][x] {
+    for new_gear in &self.gear_map.neighbors[(x, y)] {
       if !self.current_gears.contains(new_gear) {
         self.current_gears.push(*new_gear);
       }
@@ -183,7 +171,8 @@ impl NumberProcessor for GearCounter {
 /// Each '*' that is adjacent to exactly two numbers has a gear ratio that is
 /// the product of those two numbers. Return the sum of the gear ratios.
 pub fn part2(board: &Board) -> i32 {
-  let mut gear_map = GearMap{neighbors: vec![vec![SmallVec::new(); board.width]; board.height],
+  let mut gear_map = GearMap{
+    neighbors: Grid::filled(board.field.width(), board.field.height(), SmallVec::new()),
     gear_count: 0};
   find_neighbors(&mut gear_map, board, |ch| ch == '*');
   let gear_count = gear_map.gear_count;
@@ -196,11 +185,7 @@ pub fn part2(board: &Board) -> i32 {
       .sum()
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day3::{generator, part1, part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "467..114..
 ...*......
 ..35..633.
@@ -212,13 +197,23 @@ mod tests {
 ...$.*....
 .664.598..";
 
+pub const INFO: DayInfo = DayInfo{title: "Gear Ratios", year: 2023, day: 3, examples: &[
+  Example{input: EXAMPLE, part1: Some("4361"), part2: Some("467835")}],
+  tags: &["grid"]};
+
+crate::solution!(Board);
+
+#[cfg(test)]
+mod tests {
+  use crate::day3::{generator, part1, part2, EXAMPLE};
+
   #[test]
   fn test_part1() {
-    assert_eq!(4361, part1(&generator(INPUT)));
+    assert_eq!(4361, part1(&generator(EXAMPLE).unwrap()));
   }
 
   #[test]
   fn test_part2() {
-    assert_eq!(467835, part2(&generator(INPUT)));
+    assert_eq!(467835, part2(&generator(EXAMPLE).unwrap()));
   }
 }
@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use crate::utils::{DayInfo, Example, ParseError};
 
 #[derive(Debug)]
 pub struct Card {
@@ -6,19 +7,20 @@ pub struct Card {
   picks: Vec<u32>,
 }
 
-fn read_numbers(s: &str) -> Result<Vec<u32>, String> {
+fn read_numbers(s: &str) -> Result<Vec<u32>, ParseError> {
   let mut result = s.split_whitespace()
-      .map(|w| w.parse::<u32>().map_err(|_| format!("Can't parse number {w}")))
-      .collect::<Result<Vec<u32>, String>>()?;
+      .map(|w| w.parse::<u32>().map_err(|_| ParseError::new(w, "Can't parse number")))
+      .collect::<Result<Vec<u32>, ParseError>>()?;
   result.sort_unstable();
   Ok(result)
 }
 
 impl Card {
-  fn from_str(s: &str) -> Result<Self,String> {
-    let (_, contents) = s.split_once(':').ok_or("Can't find header separator")?;
+  fn from_str(s: &str) -> Result<Self,ParseError> {
+    let (_, contents) = s.split_once(':')
+        .ok_or_else(|| ParseError::new(s, "Can't find header separator"))?;
     let (win_str, pick_str) = contents.split_once('|')
-        .ok_or("Can't find wins separator")?;
+        .ok_or_else(|| ParseError::new(contents, "Can't find wins separator"))?;
     Ok(Card{wins: read_numbers(win_str)?, picks: read_numbers(pick_str)?})
   }
 
@@ -50,12 +52,8 @@ impl Card {
   }
 }
 
-pub fn generator(input: &str) -> Vec<Card> {
-  input.lines()
-      .map(|l| Card::from_str(l)
-          .map_err(|e| format!("Can't parse game with error [{e}] in {l}")))
-      .collect::<Result<Vec<Card>, String>>()
-      .unwrap() // panics on error
+pub fn generator(input: &str) -> Result<Vec<Card>, ParseError> {
+  input.lines().map(Card::from_str).collect()
 }
 
 pub fn part1(cards: &[Card]) -> i32 {
@@ -73,11 +71,7 @@ pub fn part2(cards: &[Card]) -> i32 {
   counts.iter().sum()
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day4::{generator, part1, part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
 Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
 Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
@@ -85,13 +79,23 @@ Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
+pub const INFO: DayInfo = DayInfo{title: "Scratchcards", year: 2023, day: 4, examples: &[
+  Example{input: EXAMPLE, part1: Some("13"), part2: Some("30")}],
+  tags: &[]};
+
+crate::solution!(Vec<Card>);
+
+#[cfg(test)]
+mod tests {
+  use crate::day4::{generator, part1, part2, EXAMPLE};
+
   #[test]
   fn test_part1() {
-    assert_eq!(13, part1(&generator(INPUT)));
+    assert_eq!(13, part1(&generator(EXAMPLE).unwrap()));
   }
 
   #[test]
   fn test_part2() {
-    assert_eq!(30, part2(&generator(INPUT)));
+    assert_eq!(30, part2(&generator(EXAMPLE).unwrap()));
   }
 }
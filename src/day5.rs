@@ -1,5 +1,6 @@
 use itertools::Itertools;
 use std::ops::Range;
+use crate::utils::{DayInfo, Example, IntervalSet, ParseError, blocks};
 
 #[derive(Debug,Eq,PartialEq)]
 pub struct Rule {
@@ -7,17 +8,17 @@ pub struct Rule {
   offset: i64, // what is added to the source to move to the destination
 }
 
-fn parse_int(s: Option<&str>, field_name: &str) -> Result<i64, String> {
-  s.ok_or(format!("missing field {field_name}"))?.parse()
-      .map_err(|_| format!("Can't parse integer - {}", s.unwrap()))
+fn parse_int(line: &str, s: Option<&str>, field_name: &str) -> Result<i64, ParseError> {
+  let s = s.ok_or_else(|| ParseError::new(line, format!("missing field {field_name}")))?;
+  s.parse().map_err(|_| ParseError::new(s, "Can't parse integer"))
 }
 
 impl Rule {
-  fn from_str(s: &str) -> Result<Self,String> {
+  fn from_str(s: &str) -> Result<Self,ParseError> {
     let mut word_itr = s.split_whitespace();
-    let destination = parse_int(word_itr.next(), "destination")?;
-    let source = parse_int(word_itr.next(), "source")?;
-    let length = parse_int(word_itr.next(), "length")?;
+    let destination = parse_int(s, word_itr.next(), "destination")?;
+    let source = parse_int(s, word_itr.next(), "source")?;
+    let length = parse_int(s, word_itr.next(), "length")?;
     Ok(Rule{source:source..source+length, offset:destination-source})
   }
 
@@ -36,9 +37,9 @@ pub struct KindTranslation {
 }
 
 impl KindTranslation {
-  fn from_str(s: &str) -> Result<Self, String> {
+  fn from_str(s: &str) -> Result<Self, ParseError> {
     let mut rules = s.lines().skip(1).map(Rule::from_str)
-        .collect::<Result<Vec<Rule>, String>>()?;
+        .collect::<Result<Vec<Rule>, ParseError>>()?;
     rules.sort_unstable_by_key(|r| r.source.start);
     Ok(KindTranslation{rules})
   }
@@ -47,33 +48,8 @@ impl KindTranslation {
     self.rules.iter().filter_map(|r| r.apply(val)).next().unwrap_or(val)
   }
 
-  fn translate_ranges(&self, ranges: &[Range<i64>]) -> Vec<Range<i64>> {
-    let mut result = Vec::new();
-    for rng in ranges {
-      let mut current_rule = 0;
-      let mut current_val = rng.start;
-      while current_val < rng.end {
-        // Look through the rules until we find one that may be active
-        while current_rule < self.rules.len() &&
-            self.rules[current_rule].source.end <= current_val {
-          current_rule += 1;
-        }
-        if current_rule == self.rules.len() {
-          result.push(current_val..rng.end);
-          current_val = rng.end;
-        } else if current_val < self.rules[current_rule].source.start {
-          let new_end = rng.end.min(self.rules[current_rule].source.start);
-          result.push(current_val..new_end);
-          current_val = new_end;
-        } else {
-          let new_end = rng.end.min(self.rules[current_rule].source.end);
-          let offset = self.rules[current_rule].offset;
-          result.push(current_val+offset..new_end+offset);
-          current_val = new_end;
-        }
-      }
-    }
-    result
+  fn translate_ranges(&self, ranges: &IntervalSet<i64>) -> IntervalSet<i64> {
+    ranges.shift_pieces(self.rules.iter().map(|r| (r.source.clone(), r.offset)))
   }
 }
 
@@ -84,15 +60,16 @@ pub struct Almanac {
 }
 
 impl Almanac {
-  fn from_str(s: &str) -> Result<Self, String> {
-    let mut itr = s.split("\n\n");
-    let (_, seed_list) = itr.next().ok_or("Missing seed deliminator")?
-        .split_once(':').ok_or("Missing seed list")?;
+  fn from_str(s: &str) -> Result<Self, ParseError> {
+    let mut itr = blocks(s);
+    let header = itr.next().ok_or_else(|| ParseError::new(s, "Missing seed deliminator"))?;
+    let (_, seed_list) = header.split_once(':')
+        .ok_or_else(|| ParseError::new(header, "Missing seed list"))?;
     let seeds: Vec<i64> = seed_list.split_whitespace()
-        .map(|w| w.parse::<i64>().map_err(|_| format!("Can't parse integer {w}")))
-        .collect::<Result<Vec<i64>,String>>()?;
+        .map(|w| w.parse::<i64>().map_err(|_| ParseError::new(w, "Can't parse integer")))
+        .collect::<Result<Vec<i64>,ParseError>>()?;
     Ok(Almanac{seeds, translations: itr.map(KindTranslation::from_str)
-        .collect::<Result<Vec<KindTranslation>,String>>()?})
+        .collect::<Result<Vec<KindTranslation>,ParseError>>()?})
   }
 
   fn translate(&self, seed: i64) -> i64 {
@@ -103,8 +80,8 @@ impl Almanac {
     val
   }
 
-  fn translate_ranges(&self, seed: &[Range<i64>]) -> Vec<Range<i64>> {
-    let mut val = seed.to_vec();
+  fn translate_ranges(&self, seed: &IntervalSet<i64>) -> IntervalSet<i64> {
+    let mut val = seed.clone();
     for tr in &self.translations {
       val = tr.translate_ranges(&val);
     }
@@ -112,9 +89,8 @@ impl Almanac {
   }
 }
 
-pub fn generator(input: &str) -> Almanac {
+pub fn generator(input: &str) -> Result<Almanac, ParseError> {
   Almanac::from_str(input)
-      .unwrap() // panics on error
 }
 
 pub fn part1(almanac: &Almanac) -> i64 {
@@ -123,15 +99,11 @@ pub fn part1(almanac: &Almanac) -> i64 {
 
 pub fn part2(almanac: &Almanac) -> i64 {
   let seed_ranges = almanac.seeds.iter().tuples::<(_,_)>()
-      .map(|(start,len)| *start..*start+*len).collect::<Vec<Range<i64>>>();
-  almanac.translate_ranges(&seed_ranges).iter().map(|r| r.start).min().unwrap()
+      .map(|(start,len)| *start..*start+*len).collect::<IntervalSet<i64>>();
+  almanac.translate_ranges(&seed_ranges).min().unwrap()
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day5::{generator, KindTranslation, part1, part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "seeds: 79 14 55 13
 
 seed-to-soil map:
@@ -166,25 +138,38 @@ humidity-to-location map:
 60 56 37
 56 93 4";
 
+pub const INFO: DayInfo = DayInfo{title: "If You Give A Seed A Fertilizer", year: 2023, day: 5, examples: &[
+  Example{input: EXAMPLE, part1: Some("35"), part2: Some("46")}],
+  tags: &[]};
+
+crate::solution!(Almanac);
+
+#[cfg(test)]
+mod tests {
+  use crate::day5::{generator, KindTranslation, part1, part2, EXAMPLE};
+  use crate::utils::IntervalSet;
+
   #[test]
   fn test_part1() {
-    assert_eq!(35, part1(&generator(INPUT)));
+    assert_eq!(35, part1(&generator(EXAMPLE).unwrap()));
   }
 
   #[test]
   fn test_translation() {
     let trans =
         KindTranslation::from_str("foo map:\n100 1000 50\n200 2000 25").unwrap();
-    assert_eq!(vec![1..1000, 100..150, 1050..2000, 200..225, 2025..3000],
-               trans.translate_ranges(&[1..3000]));
-    assert_eq!(vec![103..105],
-               trans.translate_ranges(&[1003..1005]));
-    assert_eq!(vec![4000..4011],
-               trans.translate_ranges(&[4000..4011]));
+    assert_eq!(IntervalSet::from_iter([1..1000, 100..150, 1050..2000, 200..225, 2025..3000]),
+               trans.translate_ranges(&IntervalSet::from(1..3000)));
+    assert_eq!(&[100..150, 1050..1100],
+               trans.translate_ranges(&IntervalSet::from(1000..1100)).ranges());
+    assert_eq!(IntervalSet::from(103..105),
+               trans.translate_ranges(&IntervalSet::from(1003..1005)));
+    assert_eq!(IntervalSet::from(4000..4011),
+               trans.translate_ranges(&IntervalSet::from(4000..4011)));
   }
 
   #[test]
   fn test_part2() {
-    assert_eq!(46, part2(&generator(INPUT)));
+    assert_eq!(46, part2(&generator(EXAMPLE).unwrap()));
   }
 }
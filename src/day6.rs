@@ -1,22 +1,24 @@
+use crate::utils::{DayInfo, Example, ParseError};
+
 #[derive(Debug)]
 pub struct Race {
   time: u64,
   record: u64,
 }
 
-fn parse_ints(s: Option<&str>, field_name: &str) -> Result<Vec<u64>, String> {
-  s.ok_or(format!("missing field {field_name}"))?
+fn parse_ints(input: &str, s: Option<&str>, field_name: &str) -> Result<Vec<u64>, ParseError> {
+  s.ok_or_else(|| ParseError::new(input, format!("missing field {field_name}")))?
       .split_whitespace()
       .skip(1)
-      .map(|w| w.parse().map_err(|_| format!("Can't parse integer - {}", s.unwrap())))
+      .map(|w| w.parse().map_err(|_| ParseError::new(w, "Can't parse integer")))
       .collect()
 }
 
 impl Race {
-  fn from_str(s: &str) -> Result<Vec<Self>,String> {
+  fn from_str(s: &str) -> Result<Vec<Self>,ParseError> {
     let mut lines = s.lines();
-    let times = parse_ints(lines.next(), "time")?;
-    let records = parse_ints(lines.next(), "records")?;
+    let times = parse_ints(s, lines.next(), "time")?;
+    let records = parse_ints(s, lines.next(), "records")?;
     Ok(times.iter().zip(records.iter())
         .map(|(t, r)| Race{time: *t, record: *r}).collect())
   }
@@ -24,7 +26,7 @@ impl Race {
   fn find_wins(&self) -> u64 {
     let time = self.time as f64;
     let swing = f64::sqrt(time * time - 4.0 * self.record as f64) / 2.0;
-    if self.time % 2 == 0 {
+    if self.time.is_multiple_of(2) {
       swing.ceil() as u64 * 2 - 1
     } else {
       (swing - 0.5).ceil() as u64 * 2
@@ -32,9 +34,8 @@ impl Race {
   }
 }
 
-pub fn generator(input: &str) -> Vec<Race> {
+pub fn generator(input: &str) -> Result<Vec<Race>, ParseError> {
   Race::from_str(input)
-      .unwrap() // panics on error
 }
 
 pub fn part1(races: &[Race]) -> u64 {
@@ -55,21 +56,27 @@ pub fn part2(races: &[Race]) -> u64 {
   Race{time, record}.find_wins()
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day6::{generator, part1, part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "Time:      7  15   30
 Distance:  9  40  200";
 
+pub const INFO: DayInfo = DayInfo{title: "Wait For It", year: 2023, day: 6, examples: &[
+  Example{input: EXAMPLE, part1: Some("288"), part2: Some("71503")}],
+  tags: &[]};
+
+crate::solution!(Vec<Race>);
+
+#[cfg(test)]
+mod tests {
+  use crate::day6::{generator, part1, part2, EXAMPLE};
+
   #[test]
   fn test_part1() {
-    assert_eq!(288, part1(&generator(INPUT)));
+    assert_eq!(288, part1(&generator(EXAMPLE).unwrap()));
   }
 
   #[test]
   fn test_part2() {
-    assert_eq!(71503, part2(&generator(INPUT)));
+    assert_eq!(71503, part2(&generator(EXAMPLE).unwrap()));
   }
 }
@@ -1,3 +1,5 @@
+use crate::utils::{DayInfo, Example, ParseError};
+
 #[derive(Clone,Copy,Debug,Eq,Ord,PartialEq,PartialOrd)]
 pub enum Rank {
   WildJack, Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten, Jack, Queen, King, Ace,
@@ -23,8 +25,11 @@ impl Rank {
     }
   }
 
-  fn from_str(s: &str) -> Result<Vec<Rank>, String> {
-    s.chars().map(Rank::from_char).collect()
+  fn from_str(s: &str) -> Result<Vec<Rank>, ParseError> {
+    s.char_indices()
+        .map(|(i, ch)| Rank::from_char(ch)
+            .map_err(|e| ParseError::new(&s[i..i + ch.len_utf8()], e)))
+        .collect()
   }
 }
 
@@ -69,20 +74,20 @@ impl Hand {
     }
   }
 
-  fn from_str(s: &str) -> Result<Self, String> {
+  fn from_str(s: &str) -> Result<Self, ParseError> {
     let mut words = s.split_whitespace();
-    let cards: [Rank; HAND_SIZE] = Rank::from_str(words.next()
-        .ok_or(format!("Missing cards in {s}"))?)?.try_into().unwrap();
-    let bid = words.next().ok_or("Missing bid")?
-        .parse::<u64>().map_err(|_| format!("Can't parse bid in {s}"))?;
-    let kind = Self::get_kind(&cards)?;
+    let card_str = words.next().ok_or_else(|| ParseError::new(s, "Missing cards"))?;
+    let cards: [Rank; HAND_SIZE] = Rank::from_str(card_str)?.try_into()
+        .map_err(|_| ParseError::new(card_str, format!("Need {HAND_SIZE} cards")))?;
+    let bid_str = words.next().ok_or_else(|| ParseError::new(s, "Missing bid"))?;
+    let bid = bid_str.parse::<u64>().map_err(|_| ParseError::new(bid_str, "Can't parse bid"))?;
+    let kind = Self::get_kind(&cards).map_err(|e| ParseError::new(card_str, e))?;
     Ok(Hand { kind, cards, bid })
   }
 }
 
-pub fn generator(input: &str) -> Vec<Hand> {
-  input.lines().map(Hand::from_str).collect::<Result<Vec<Hand>, String>>()
-      .unwrap() // panics on error
+pub fn generator(input: &str) -> Result<Vec<Hand>, ParseError> {
+  input.lines().map(Hand::from_str).collect()
 }
 
 pub fn part1(input: &[Hand]) -> u64 {
@@ -105,24 +110,30 @@ pub fn part2(input: &[Hand]) -> u64 {
   hands.iter().enumerate().map(|(i, c) | (i as u64 + 1) * c.bid).sum()
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day7::{generator, part1, part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "32T3K 765
 T55J5 684
 KK677 28
 KTJJT 220
 QQQJA 483";
 
+pub const INFO: DayInfo = DayInfo{title: "Camel Cards", year: 2023, day: 7, examples: &[
+  Example{input: EXAMPLE, part1: Some("6440"), part2: Some("5905")}],
+  tags: &[]};
+
+crate::solution!(Vec<Hand>);
+
+#[cfg(test)]
+mod tests {
+  use crate::day7::{generator, part1, part2, EXAMPLE};
+
   #[test]
   fn test_part1() {
-    assert_eq!(6440, part1(&generator(INPUT)));
+    assert_eq!(6440, part1(&generator(EXAMPLE).unwrap()));
   }
 
   #[test]
   fn test_part2() {
-    assert_eq!(5905, part2(&generator(INPUT)));
+    assert_eq!(5905, part2(&generator(EXAMPLE).unwrap()));
   }
 }
@@ -1,5 +1,7 @@
 use num_integer::Integer;
 use std::collections::HashMap;
+use crate::utils::{DayInfo, Example, ParseError, blocks};
+use crate::utils::cycle::{find_cycle, Memory};
 
 #[derive(Clone,Copy,Debug,Eq,PartialEq)]
 pub enum Direction {
@@ -7,16 +9,19 @@ pub enum Direction {
 }
 
 impl Direction {
-  fn from_char(ch: char) -> Result<Direction, String> {
+  fn from_char(ch: char) -> Option<Direction> {
     match ch {
-      'L' => Ok(Direction::Left),
-      'R' => Ok(Direction::Right),
-      _ => Err(format!("Unknown direction - {ch}")),
+      'L' => Some(Direction::Left),
+      'R' => Some(Direction::Right),
+      _ => None,
     }
   }
 
-  fn from_str(s: &str) -> Result<Vec<Direction>, String> {
-    s.chars().map(Direction::from_char).collect()
+  fn from_str(s: &str) -> Result<Vec<Direction>, ParseError> {
+    s.char_indices()
+        .map(|(i, ch)| Direction::from_char(ch)
+            .ok_or_else(|| ParseError::new(&s[i..i + ch.len_utf8()], "Unknown direction")))
+        .collect()
   }
 }
 
@@ -29,13 +34,17 @@ pub struct Location {
 }
 
 impl Location {
-  fn from_str(s: &str, location_map: &HashMap<String, usize>) -> Result<Self, String> {
+  fn from_str(s: &str, location_map: &HashMap<String, usize>) -> Result<Self, ParseError> {
     let (name, targets) = s.split_once(" = ")
-        .ok_or("Can't find divider in {s}")?;
-    let (left, right) = targets[1..targets.len() -1].split_once(", ")
-        .ok_or("Can't split destinations in {s}")?;
-    Ok(Location{name: name.to_string(), left: *location_map.get(left).unwrap(),
-      right: *location_map.get(right).unwrap(), ends_with_z: name.ends_with('Z')})
+        .ok_or_else(|| ParseError::new(s, "Can't find divider"))?;
+    let (left, right) = targets.strip_prefix('(')
+        .and_then(|t| t.strip_suffix(')'))
+        .and_then(|t| t.split_once(", "))
+        .ok_or_else(|| ParseError::new(targets, "Can't split destinations"))?;
+    let find = |target: &str| location_map.get(target).copied()
+        .ok_or_else(|| ParseError::new(target, "Unknown location"));
+    Ok(Location{name: name.to_string(), left: find(left)?, right: find(right)?,
+      ends_with_z: name.ends_with('Z')})
   }
 }
 
@@ -51,18 +60,20 @@ impl Map {
   const START_LOCATION: &'static str = "AAA";
   const GOAL_LOCATION: &'static str = "ZZZ";
 
-  fn from_str(s: &str) -> Result<Self, String> {
-    let (dir_str, room_str) = s.split_once("\n\n")
-        .ok_or("Can't find locations!")?;
+  fn from_str(s: &str) -> Result<Self, ParseError> {
+    let mut itr = blocks(s);
+    let (dir_str, room_str) = itr.next().zip(itr.next())
+        .ok_or_else(|| ParseError::new(s, "Can't find locations!"))?;
     let directions = Direction::from_str(dir_str)?;
     let mut location_map: HashMap<String, usize> = HashMap::new();
     for (i, l) in room_str.lines().enumerate() {
-      let (id, _) = l.split_once(" = ").ok_or(format!("Can't get name of {l}"))?;
+      let (id, _) = l.split_once(" = ")
+          .ok_or_else(|| ParseError::new(l, "Can't get name"))?;
       location_map.insert(id.to_string(), i);
     }
     let places = room_str.lines()
         .map(|l| Location::from_str(l, &location_map))
-        .collect::<Result<Vec<Location>,String>>()?;
+        .collect::<Result<Vec<Location>,ParseError>>()?;
     Ok(Map{start: location_map.get(Self::START_LOCATION).copied(),
            goal: location_map.get(Self::GOAL_LOCATION).copied(),
            directions, places})
@@ -78,8 +89,8 @@ impl Map {
   }
 }
 
-pub fn generator(input: &str) -> Map {
-  Map::from_str(input).unwrap() // panics on error
+pub fn generator(input: &str) -> Result<Map, ParseError> {
+  Map::from_str(input)
 }
 
 pub fn part1(input: &Map) -> usize {
@@ -104,22 +115,18 @@ struct CycleDescription {
 
 impl CycleDescription {
   fn from_map(input: &Map, start: usize) -> Self {
-    let mut visited: Vec<Vec<Option<usize>>>
-        = vec![vec![None; input.places.len()]; input.directions.len()];
+    // The state is the position in the directions and the current location.
     let mut goals = Vec::new();
-    let mut current = start;
-    for (step, dir) in input.directions.iter().cloned().cycle().enumerate() {
-      let loc = &input.places[current];
-      if let Some(loop_start) = visited[step % input.directions.len()][current] {
-        return CycleDescription { goals, start: loop_start, length: step - loop_start }
-      }
-      if loc.ends_with_z {
+    let mut step = 0;
+    let cycle = find_cycle((0, start), |(index, current)| {
+      if input.places[*current].ends_with_z {
         goals.push(step);
       }
-      visited[step % input.directions.len()][current] = Some(step);
-      current = input.step(current, dir);
-    }
-    panic!("Shouldn't end loop!");
+      *current = input.step(*current, input.directions[*index]);
+      *index = (*index + 1) % input.directions.len();
+      step += 1;
+    }, Memory::States);
+    CycleDescription { goals, start: cycle.prefix, length: cycle.period }
   }
 
   /// Is this a simple loop where the loop has a single goal at the end of the loop?
@@ -206,11 +213,7 @@ pub fn part2(input: &Map) -> usize {
   }
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day8::{CycleDescription, generator, part1, part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "RL
 
 AAA = (BBB, CCC)
@@ -221,12 +224,7 @@ EEE = (EEE, EEE)
 GGG = (GGG, GGG)
 ZZZ = (ZZZ, ZZZ)";
 
-  #[test]
-  fn test_part1() {
-    assert_eq!(2, part1(&generator(INPUT)));
-  }
-
-  const INPUT2: &str =
+const EXAMPLE2: &str =
 "LR
 
 11A = (11B, XXX)
@@ -238,9 +236,25 @@ ZZZ = (ZZZ, ZZZ)";
 22Z = (22B, 22B)
 XXX = (XXX, XXX)";
 
+pub const INFO: DayInfo = DayInfo{title: "Haunted Wasteland", year: 2023, day: 8, examples: &[
+  Example{input: EXAMPLE, part1: Some("2"), part2: None},
+  Example{input: EXAMPLE2, part1: None, part2: Some("6")}],
+  tags: &["graph"]};
+
+crate::solution!(Map);
+
+#[cfg(test)]
+mod tests {
+  use crate::day8::{CycleDescription, generator, part1, part2, EXAMPLE, EXAMPLE2};
+
+  #[test]
+  fn test_part1() {
+    assert_eq!(2, part1(&generator(EXAMPLE).unwrap()));
+  }
+
   #[test]
   fn test_part2() {
-    assert_eq!(6, part2(&generator(INPUT2)));
+    assert_eq!(6, part2(&generator(EXAMPLE2).unwrap()));
   }
 
   const INPUT3: &str =
@@ -257,13 +271,13 @@ XXX = (XXX, XXX)";
 
   #[test]
   fn test_iter() {
-    let input = generator(INPUT2);
+    let input = generator(EXAMPLE2).unwrap();
     let cycle = CycleDescription::from_map(&input, 0);
     assert_eq!(vec![2, 4, 6, 8], cycle.iter().take(4).collect::<Vec<usize>>());
     let cycle = CycleDescription::from_map(&input, 3);
     assert_eq!(vec![3, 6, 9, 12], cycle.iter().take(4).collect::<Vec<usize>>());
 
-    let input = generator(INPUT3);
+    let input = generator(INPUT3).unwrap();
     let cycle = CycleDescription::from_map(&input, 0);
     assert_eq!(vec!{1, 3, 5, 8, 12, 14, 16, 20, 23, 27, 29, 31, 35, 38},
                cycle.iter().take(14).collect::<Vec<usize>>());
@@ -1,14 +1,15 @@
+use crate::utils::{DayInfo, Example, ParseError};
+
 type ValueType = i64;
 
-fn read_numbers(s: &str) -> Result<Vec<ValueType>, String> {
+fn read_numbers(s: &str) -> Result<Vec<ValueType>, ParseError> {
   s.split_whitespace()
-      .map(|w| w.parse::<ValueType>().map_err(|_| format!("Can't parse number {w}")))
-      .collect::<Result<Vec<ValueType>, String>>()
+      .map(|w| w.parse::<ValueType>().map_err(|_| ParseError::new(w, "Can't parse number")))
+      .collect::<Result<Vec<ValueType>, ParseError>>()
 }
 
-pub fn generator(input: &str) -> Vec<Vec<ValueType>> {
-  input.lines().map(read_numbers).collect::<Result<Vec<Vec<ValueType>>,String>>()
-      .unwrap() // panics on error
+pub fn generator(input: &str) -> Result<Vec<Vec<ValueType>>, ParseError> {
+  input.lines().map(read_numbers).collect()
 }
 
 fn build_differences(series: &[ValueType]) -> Vec<ValueType> {
@@ -40,23 +41,29 @@ pub fn part2(input: &[Vec<ValueType>]) -> ValueType {
   input.iter().map(|v| find_previous(v)).sum()
 }
 
-#[cfg(test)]
-mod tests {
-  use crate::day9::{generator, part1, part2};
-
-  const INPUT: &str =
+const EXAMPLE: &str =
 "0 3 6 9 12 15
 1 3 6 10 15 21
 10 13 16 21 30 45
 ";
 
+pub const INFO: DayInfo = DayInfo{title: "Mirage Maintenance", year: 2023, day: 9, examples: &[
+  Example{input: EXAMPLE, part1: Some("114"), part2: Some("2")}],
+  tags: &[]};
+
+crate::solution!(Vec<Vec<ValueType>>);
+
+#[cfg(test)]
+mod tests {
+  use crate::day9::{generator, part1, part2, EXAMPLE};
+
   #[test]
   fn test_part1() {
-    assert_eq!(114, part1(&generator(INPUT)));
+    assert_eq!(114, part1(&generator(EXAMPLE).unwrap()));
   }
 
   #[test]
   fn test_part2() {
-    assert_eq!(2, part2(&generator(INPUT)));
+    assert_eq!(2, part2(&generator(EXAMPLE).unwrap()));
   }
 }
@@ -1,3 +1,3 @@
 pub mod utils;
 
-day_list!(1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21,22,23,25);
\ No newline at end of file
+day_list!(1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21,22,23,24,25);
\ No newline at end of file
@@ -1,29 +1,185 @@
 use std::time;
-use omalley_aoc2023::{FUNCS, NAMES, utils};
+use omalley_aoc2023::{DAYS, utils};
+use omalley_aoc2023::utils::history::{TimingEntry, TimingHistory};
+use omalley_aoc2023::utils::pool::Outcome;
 
 use argh::FromArgs;
 use colored::Colorize;
 use serde::{Deserialize,Serialize};
 use std::collections::BTreeMap;
 use std::fs::File;
+use std::io::{self, Write};
 use std::path::Path;
+use std::panic;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[derive(FromArgs)]
 /** Solution for Advent of Code (https://adventofcode.com/)*/
 struct Args {
-  /// the input directory
-  #[argh(option, short='i', default="String::from(\"input\")")]
-  input: String,
+  /// the input directory, which may be repeated (defaults to input)
+  #[argh(option, short='i')]
+  input: Vec<String>,
 
-  /// days to execute (defaults to all)
+  /// when comparing input directories, flag days whose times differ
+  /// by more than this factor
+  #[argh(option, default="2.0")]
+  time_factor: f64,
+
+  /// how many times to run each day for the timing statistics
+  #[argh(option, default="1")]
+  repeat: usize,
+
+  /// how many untimed runs of each day to do before the timed ones
+  #[argh(option, default="0")]
+  warmup: usize,
+
+  /// warn when a phase is this percent slower than the median of its
+  /// recent history
+  #[argh(option, default="25.0")]
+  regression_pct: f64,
+
+  /// fail the run when a phase regresses, instead of only warning
+  #[argh(switch)]
+  fail_on_regression: bool,
+
+  /// how many previous runs to use for the timing history's median
+  #[argh(option, default="10")]
+  history_window: usize,
+
+  /// how many days to run at the same time
+  #[argh(option, short='j', default="1")]
+  jobs: usize,
+
+  /// pin each worker thread to its own core
+  #[argh(switch)]
+  pin: bool,
+
+  /// give up on a day after this many seconds
+  #[argh(option)]
+  timeout: Option<f64>,
+
+  /// the output format: pretty, json, or csv
+  #[argh(option, default="Format::Pretty")]
+  format: Format,
+
+  /// compare the answers against answers.yml without updating it and
+  /// exit with an error if any are wrong or missing
+  #[argh(switch)]
+  check: bool,
+
+  /// record the new answers in answers.yml even if they changed
+  #[argh(switch)]
+  accept: bool,
+
+  /// run the single picked day on this file instead of the input
+  /// directory, where - reads stdin
+  #[argh(option)]
+  file: Option<String>,
+
+  /// record the answers from --file in the input directory's answers.yml
+  #[argh(switch)]
+  record: bool,
+
+  /// only run this part (1 or 2) of the days
+  #[argh(option)]
+  part: Option<usize>,
+
+  /// days to execute (defaults to all), such as 12, 12.2 for just part 2,
+  /// 1-10, 15.., a tag like grid, all-slow, or ^25 to leave a day out
   #[argh(positional)]
-  days: Vec<usize>,
+  days: Vec<String>,
+}
+
+/// How the results should be written to stdout.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+enum Format {
+  Pretty,
+  Json,
+  Csv,
+}
+
+impl FromStr for Format {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "pretty" => Ok(Format::Pretty),
+      "json" => Ok(Format::Json),
+      "csv" => Ok(Format::Csv),
+      _ => Err(format!("Unknown format - {s}")),
+    }
+  }
+}
+
+/// The machine readable form of a day's result. When the days are repeated,
+/// the times are the medians.
+#[derive(Serialize)]
+struct DayRecord<'a> {
+  input: &'a str,
+  day: &'a str,
+  status: String,
+  generate_ns: u64,
+  part1_ns: u64,
+  part2_ns: u64,
+  part1: &'a utils::Answer,
+  part2: &'a utils::Answer,
+}
+
+impl<'a> DayRecord<'a> {
+  fn new(input: &'a str, result: &'a utils::DayResult, timings: &utils::DayTimings) -> Self {
+    DayRecord{input, day: &result.day,
+      status: result.failure.as_ref().map_or_else(|| String::from("ok"), |f| f.to_string()),
+      generate_ns: timings.generate.median.as_nanos() as u64,
+      part1_ns: timings.part1.median.as_nanos() as u64,
+      part2_ns: timings.part2.median.as_nanos() as u64,
+      part1: &result.part1.1,
+      part2: &result.part2.1}
+  }
+}
+
+/// The result of comparing an answer to the stored one.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+enum Verdict {
+  Pass,
+  Fail,
+  Missing,
+  Timeout,
+  Error,
+  /// The part wasn't run.
+  Skipped,
+  /// The part isn't solved and there is no answer to compare with.
+  Unsolved,
+}
+
+impl Verdict {
+  /// Parts that weren't run or aren't solved don't count against the day.
+  fn is_ok(&self) -> bool {
+    matches!(self, Verdict::Pass | Verdict::Skipped | Verdict::Unsolved)
+  }
+
+  fn colored(&self) -> colored::ColoredString {
+    match self {
+      Verdict::Pass => "pass".green(),
+      Verdict::Fail => "FAIL".red().bold(),
+      Verdict::Missing => "MISSING".yellow().bold(),
+      Verdict::Timeout => "TIMEOUT".red().bold(),
+      Verdict::Error => "ERROR".red().bold(),
+      Verdict::Skipped => "-".dimmed(),
+      Verdict::Unsolved => "unsolved".yellow(),
+    }
+  }
 }
 
 #[derive(Default,Deserialize,Serialize)]
 struct Answers {
   // map from day name to answers
-  days: BTreeMap<String,Vec<String>>,
+  days: BTreeMap<String,Vec<utils::Answer>>,
+}
+
+/// Show a day's answers for a message.
+fn show_answers(answers: &[utils::Answer]) -> String {
+  format!("[{}]", answers.iter().map(|a| a.to_string()).collect::<Vec<String>>().join(", "))
 }
 
 impl Answers {
@@ -39,17 +195,89 @@ impl Answers {
     }
   }
 
-  fn update(&mut self, delta_list: &Vec<utils::DayResult>) {
+  /// Record the new answers. Answers that changed are only replaced if
+  /// accept is set. Only the parts that ran and were solved are updated.
+  fn update(&mut self, delta_list: &[utils::DayResult], accept: bool,
+            out: &mut dyn Write) -> io::Result<()> {
     for delta in delta_list {
-      let new_val = delta.get_answers();
-      if let Some(prev) =
-          self.days.insert(delta.day.to_string(), new_val.clone()) {
-        if prev != new_val {
-          println!("{}", format!("Output for {} changed from {:?} to {:?}!",
-                                 delta.pretty_day(), prev, new_val).bold());
+      if let Some(failure) = &delta.failure {
+        writeln!(out, "{}", format!("Not recording {}: {failure}", delta.pretty_day()).bold())?;
+        continue
+      }
+      let prev = self.days.get(&delta.day);
+      let new_val: Vec<utils::Answer> = delta.get_answers().into_iter().enumerate()
+          .map(|(i, answer)| if delta.parts.includes(i + 1) && answer.is_solved() {
+            answer
+          } else {
+            prev.and_then(|p| p.get(i)).cloned().unwrap_or_default()
+          })
+          .collect();
+      match prev {
+        Some(prev) if *prev != new_val => {
+          if accept {
+            writeln!(out, "{}", format!("Output for {} changed from {} to {}!",
+                                        delta.pretty_day(), show_answers(prev),
+                                        show_answers(&new_val)).bold())?;
+          } else {
+            writeln!(out, "{}", format!("Output for {} changed from {} to {}! \
+                                        Use --accept to record it.",
+                                        delta.pretty_day(), show_answers(prev),
+                                        show_answers(&new_val)).bold())?;
+            continue
+          }
+        }
+        _ => {}
+      }
+      self.days.insert(delta.day.to_string(), new_val);
+    }
+    Ok(())
+  }
+
+  /// Compare each part of the result against the stored answers.
+  fn verify(&self, result: &utils::DayResult) -> Vec<Verdict> {
+    match result.failure {
+      Some(utils::Failure::Timeout(..)) => return vec![Verdict::Timeout; 2],
+      Some(utils::Failure::Error(..) | utils::Failure::Parse(..)) => return vec![Verdict::Error; 2],
+      Some(utils::Failure::NoInput(..)) => return vec![Verdict::Missing; 2],
+      None => {}
+    }
+    let expected = self.days.get(&result.day);
+    result.get_answers().iter().enumerate()
+        .map(|(i, answer)| match expected.and_then(|e| e.get(i)) {
+          _ if !result.parts.includes(i + 1) => Verdict::Skipped,
+          // An unsolved answer is a placeholder for a part that was never
+          // recorded.
+          Some(e) if e.is_solved() && e == answer => Verdict::Pass,
+          Some(e) if e.is_solved() => Verdict::Fail,
+          _ if answer.is_solved() => Verdict::Missing,
+          _ => Verdict::Unsolved,
+        })
+        .collect()
+  }
+
+  /// Print a table of which answers match and return whether they all did.
+  fn check(&self, results: &[utils::DayResult], out: &mut dyn Write) -> io::Result<bool> {
+    writeln!(out, "\n{:<8} {:<10} {:<10}", "Day".bold(), "Part 1".bold(), "Part 2".bold())?;
+    let mut passed = true;
+    for result in results {
+      let verdicts = self.verify(result);
+      passed &= verdicts.iter().all(Verdict::is_ok);
+      write!(out, "{:<8}", result.pretty_day())?;
+      for v in &verdicts {
+        write!(out, " {:<10}", v.colored())?;
+      }
+      writeln!(out)?;
+      if let Some(failure) = &result.failure {
+        writeln!(out, "  {failure}")?;
+      }
+      for (v, expected) in verdicts.iter()
+          .zip(self.days.get(&result.day).into_iter().flatten()) {
+        if *v == Verdict::Fail {
+          writeln!(out, "  expected {}", expected.to_string().bold())?;
         }
       }
     }
+    Ok(passed)
   }
 
   fn write(&self, directory: &str) {
@@ -63,37 +291,310 @@ impl Answers {
   }
 }
 
-fn main() {
-  let args: Args = argh::from_env();
-  // Which days did the user pick to run?
-  let mut day_filter = [args.days.is_empty(); NAMES.len()];
-  for day in args.days {
-    let name = format!("day{day}");
-    if let Some(idx) = NAMES.iter().position(|&n| n == name) {
-      day_filter[idx] = true;
-    } else {
-      panic!("Can't find implementation for {name}.")
-    }
-  }
-  // Read the inputs from the given directory
-  println!("{} {}\n", "Reading from".bold(), &args.input);
-  let inputs = utils::read_inputs(&args.input, NAMES, &day_filter)
-      .expect("Can't read input");
-
-  let results=
-    FUNCS.iter().enumerate()
-        .filter(|(p, _)| day_filter[*p])
-        .map(|(p, f)| {
-          let result = f(&inputs[p]);
-          println!("{result}");
-          result})
-        .collect::<Vec<utils::DayResult>>();
+/// The results of running the days against one input directory.
+struct InputRun {
+  directory: String,
+  results: Vec<utils::DayResult>,
+  timings: Vec<utils::DayTimings>,
+  verdicts: Vec<Vec<Verdict>>,
+}
+
+impl InputRun {
+  fn find(&self, day: &str) -> Option<(&utils::DayResult, &Vec<Verdict>)> {
+    self.results.iter().zip(self.verdicts.iter())
+        .find(|(r, _)| r.day == day)
+  }
+}
+
+/// Run a single day warmup times untimed and then repeat times to gather
+/// its timings. The result is the run with the median total time.
+/// Each phase is recorded in the stage as it starts. If the day panics, the
+/// result holds the error instead.
+fn run_day(p: usize, parts: utils::Parts, input: &str, repeat: usize, warmup: usize,
+           stage: &AtomicUsize) -> (utils::DayResult, utils::DayTimings) {
+  let progress = |phase: utils::Phase| stage.store(phase as usize, Ordering::Relaxed);
+  let runs = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+    for _ in 0..warmup {
+      DAYS[p].run(input, parts, &progress);
+    }
+    (0..repeat.max(1)).map(|_| DAYS[p].run(input, parts, &progress))
+        .collect::<Vec<utils::DayResult>>()
+  }));
+  match runs {
+    Ok(mut samples) => {
+      let timings = utils::DayTimings::from_results(DAYS[p].name, &samples);
+      // Report the run with the median total time, which is the same rank
+      // that the timings use, rather than the cold first run.
+      samples.sort_by_key(|r| r.total_time());
+      let median = samples.len().div_ceil(2) - 1;
+      (samples.swap_remove(median), timings)
+    }
+    Err(payload) => {
+      let phase = utils::Phase::from_index(stage.load(Ordering::Relaxed));
+      (utils::DayResult::failed(DAYS[p].name, utils::Failure::from_panic(phase, &*payload)),
+       utils::DayTimings::from_results(DAYS[p].name, &[]))
+    }
+  }
+}
+
+/// Run each of the picked days against the input in the directory, or in
+/// --file if it was given, using the requested number of threads. The
+/// results are reported in day order. A day without an input is skipped,
+/// except with --check when the directory has answers for it, where it is
+/// reported as a failure so that the check can't pass without running it.
+fn run_days(directory: &str, day_filter: &[Option<utils::Parts>], answers: &Answers,
+            args: &Args, out: &mut dyn Write) -> io::Result<(Vec<utils::DayResult>, Vec<utils::DayTimings>)> {
+  let source = match args.file.as_deref() {
+    Some("-") => "stdin",
+    Some(path) => path,
+    None => directory,
+  };
+  writeln!(out, "{} {}\n", "Reading from".bold(), source)?;
+  let mut tasks = Vec::new();
+  let mut missing = Vec::new();
+  for (p, parts) in day_filter.iter().enumerate()
+      .filter_map(|(p, parts)| parts.map(|parts| (p, parts))) {
+    let input = match &args.file {
+      Some(path) => utils::read_file(path),
+      None => utils::read_input(directory, DAYS[p].name),
+    };
+    match input {
+      Ok(input) => tasks.push((p, parts, input)),
+      Err(e) if args.check && answers.days.contains_key(DAYS[p].name) => missing.push((p, e)),
+      Err(e) => writeln!(out, "{} {e}\n", "Skipping".yellow().bold())?,
+    }
+  }
+  let days: Vec<usize> = tasks.iter().map(|(p, _, _)| *p).collect();
+  let (repeat, warmup) = (args.repeat, args.warmup);
+  let timeout = args.timeout.map(time::Duration::from_secs_f64);
+  let start = time::Instant::now();
+  let mut results = Vec::new();
+  let mut timings = Vec::new();
+  let mut status: io::Result<()> = Ok(());
+  utils::pool::run_in_order(tasks, args.jobs, args.pin, timeout,
+      move |(p, parts, input), stage| run_day(p, parts, &input, repeat, warmup, stage),
+      |idx, outcome| {
+        let (result, day_timings) = match outcome {
+          Outcome::Done(done) => done,
+          Outcome::TimedOut(stage) => {
+            let failure = utils::Failure::Timeout(utils::Phase::from_index(stage),
+                                                  timeout.unwrap_or_default());
+            (utils::DayResult::failed(DAYS[days[idx]].name, failure),
+             utils::DayTimings::from_results(DAYS[days[idx]].name, &[]))
+          }
+          Outcome::Panicked(stage, message) => {
+            let failure = utils::Failure::Error(utils::Phase::from_index(stage), message);
+            (utils::DayResult::failed(DAYS[days[idx]].name, failure),
+             utils::DayTimings::from_results(DAYS[days[idx]].name, &[]))
+          }
+        };
+        if status.is_ok() {
+          status = writeln!(out, "{result}");
+        }
+        if status.is_ok() && repeat > 1 {
+          status = writeln!(out, "{day_timings}");
+        }
+        results.push((days[idx], result));
+        timings.push((days[idx], day_timings));
+      });
+  status?;
+  for (p, e) in missing {
+    let result = utils::DayResult::failed(DAYS[p].name, utils::Failure::NoInput(e));
+    writeln!(out, "{result}")?;
+    results.push((p, result));
+    timings.push((p, utils::DayTimings::from_results(DAYS[p].name, &[])));
+  }
+  results.sort_by_key(|(p, _)| *p);
+  timings.sort_by_key(|(p, _)| *p);
+  let results: Vec<utils::DayResult> = results.into_iter().map(|(_, r)| r).collect();
+  let timings: Vec<utils::DayTimings> = timings.into_iter().map(|(_, t)| t).collect();
+  let wall_clock = start.elapsed();
   let elapsed = results.iter()
-      .map(|r| r.generate_time + r.part1.0 + r.part2.0)
+      .map(|r| r.total_time())
       .sum::<time::Duration>();
-  println!("{} {}", "Overall runtime".bold(), format!("({:.2?})", elapsed).dimmed());
+  writeln!(out, "{} {} {} {}", "Overall runtime".bold(), format!("({:.2?})", elapsed).dimmed(),
+           "wall clock".bold(), format!("({:.2?})", wall_clock).dimmed())?;
+  if repeat > 1 {
+    // List the slowest days first by their median times.
+    let mut ranked: Vec<&utils::DayTimings> = timings.iter().collect();
+    ranked.sort_by_key(|t| std::cmp::Reverse(t.total.median));
+    writeln!(out, "\n{}", "Median times".bold())?;
+    for t in ranked {
+      writeln!(out, "{:>12} {}", format!("{:.2?}", t.total.median), t.day.replace("day", "Day "))?;
+    }
+  }
+  Ok((results, timings))
+}
+
+/// Write the results of all of the runs in a machine readable format.
+fn write_records(runs: &[InputRun], format: Format) -> Result<(), String> {
+  let records: Vec<DayRecord> = runs.iter()
+      .flat_map(|run| run.results.iter().zip(run.timings.iter())
+          .map(|(r, t)| DayRecord::new(&run.directory, r, t)))
+      .collect();
+  match format {
+    Format::Pretty => {}
+    Format::Json => {
+      serde_json::to_writer_pretty(io::stdout(), &records).map_err(|e| e.to_string())?;
+      println!();
+    }
+    Format::Csv => {
+      let mut writer = csv::Writer::from_writer(io::stdout());
+      for record in &records {
+        writer.serialize(record).map_err(|e| e.to_string())?;
+      }
+      writer.flush().map_err(|e| e.to_string())?;
+    }
+  }
+  Ok(())
+}
+
+/// Print the answers and times for each day side by side across the input
+/// directories. Flag the days where only some of the inputs pass or where the
+/// times differ by more than the given factor.
+fn compare_inputs(runs: &[InputRun], time_factor: f64, out: &mut dyn Write) -> io::Result<()> {
+  let width = runs.iter().map(|r| r.directory.len()).max().unwrap_or(0);
+  writeln!(out, "\n{}", "Comparison".bold())?;
+  for name in DAYS.iter().map(|d| d.name) {
+    let found: Vec<(&str, &utils::DayResult, &Vec<Verdict>)> = runs.iter()
+        .filter_map(|r| r.find(name).map(|(d, v)| (r.directory.as_str(), d, v)))
+        .collect();
+    if let Some((_, first, _)) = found.first() {
+      writeln!(out, "{}", first.pretty_day().bold())?;
+    }
+    for (directory, result, verdicts) in &found {
+      write!(out, "  {directory:<width$} {:>12}", format!("({:.2?})", result.total_time()).dimmed())?;
+      for v in verdicts.iter() {
+        write!(out, " {:<7}", v.colored())?;
+      }
+      match &result.failure {
+        Some(failure) => writeln!(out, " {failure}")?,
+        None => writeln!(out, " {}", result.get_answers().iter()
+            .map(|a| a.to_string()).collect::<Vec<String>>().join(" "))?,
+      }
+    }
+    let passing = found.iter()
+        .filter(|(_, _, v)| v.iter().all(Verdict::is_ok))
+        .count();
+    if passing > 0 && passing < found.len() {
+      writeln!(out, "  {}", format!("only {passing} of {} inputs pass", found.len()).red().bold())?;
+    }
+    let times: Vec<f64> = found.iter()
+        .map(|(_, r, _)| r.total_time().as_secs_f64()).collect();
+    let fastest = times.iter().cloned().fold(f64::INFINITY, f64::min);
+    let slowest = times.iter().cloned().fold(0.0, f64::max);
+    if times.len() > 1 && fastest > 0.0 && slowest / fastest > time_factor {
+      writeln!(out, "  {}", format!("times differ by {:.1}x", slowest / fastest).yellow().bold())?;
+    }
+  }
+  Ok(())
+}
+
+/// Compare the timings to the history for the directory and then add them to it.
+/// Returns false if any of the phases regressed.
+fn record_timings(directory: &str, results: &[utils::DayResult], timings: &[utils::DayTimings],
+                  args: &Args, out: &mut dyn Write) -> Result<bool, String> {
+  let history = TimingHistory::read(directory)?;
+  let now = chrono::Local::now();
+  // Days that didn't finish don't have any timings, and the days that only
+  // ran one part would skew the history.
+  let entries: Vec<TimingEntry> = results.iter().zip(timings.iter())
+      .filter(|(r, t)| t.total.samples > 0 && r.parts == utils::Parts::Both)
+      .map(|(_, t)| TimingEntry::new(t, &now)).collect();
+  let mut ok = true;
+  for entry in &entries {
+    for regression in history.find_regressions(entry, args.history_window,
+                                               args.regression_pct) {
+      ok = false;
+      writeln!(out, "{} {regression}", "Slower:".yellow().bold())
+          .map_err(|e| e.to_string())?;
+    }
+  }
+  TimingHistory::append(directory, &entries).map_err(|e| e.to_string())?;
+  Ok(ok)
+}
+
+/// Make sure that --file is only used with a single day and input directory,
+/// since the answers in the directory are for a different input.
+fn check_file_args(args: &Args, day_filter: &[Option<utils::Parts>]) -> Result<(), String> {
+  if args.file.is_none() {
+    return if args.record { Err(String::from("--record only applies to --file.")) } else { Ok(()) }
+  }
+  let picked = day_filter.iter().filter(|p| p.is_some()).count();
+  if args.days.is_empty() || picked != 1 {
+    Err(format!("--file runs a single day, but {picked} were picked."))
+  } else if args.check {
+    Err(String::from("--check can't be used with --file."))
+  } else if args.input.len() > 1 {
+    Err(String::from("--file can only record into one input directory."))
+  } else {
+    Ok(())
+  }
+}
 
-  let mut old_answers = Answers::read(&args.input);
-  old_answers.update(&results);
-  old_answers.write(&args.input);
+fn main() {
+  let mut args: Args = argh::from_env();
+  if args.check && args.accept {
+    eprintln!("--check and --accept can't be used together.");
+    std::process::exit(2);
+  }
+  let day_filter = match args.part.map(utils::Parts::only).transpose()
+      .and_then(|parts| utils::pick_days(&args.days, parts.unwrap_or_default()))
+      .and_then(|filter| check_file_args(&args, &filter).map(|_| filter)) {
+    Ok(filter) => filter,
+    Err(e) => {
+      eprintln!("{e}");
+      std::process::exit(2);
+    }
+  };
+  let directories = if args.input.is_empty() {
+    vec![String::from("input")]
+  } else {
+    std::mem::take(&mut args.input)
+  };
+  // Answers from --file are only recorded when asked for with --record.
+  let record = args.file.is_none() || args.record;
+
+  // Only the pretty format writes the human readable report to stdout.
+  let mut out: Box<dyn Write> = if args.format == Format::Pretty {
+    Box::new(io::stdout())
+  } else {
+    Box::new(io::stderr())
+  };
+
+  let mut passed = true;
+  let mut runs = Vec::new();
+  for directory in directories {
+    let mut old_answers = if record { Answers::read(&directory) } else { Answers::default() };
+    let (results, timings) =
+        run_days(&directory, &day_filter, &old_answers, &args, &mut out)
+            .expect("Can't write report");
+    if args.check {
+      passed &= old_answers.check(&results, &mut out).expect("Can't write report");
+    }
+    let verdicts = results.iter().map(|r| old_answers.verify(r)).collect();
+    if !args.check && record {
+      old_answers.update(&results, args.accept, &mut out).expect("Can't write report");
+      old_answers.write(&directory);
+    }
+    // The timings from --file aren't for the directory's input, so they
+    // stay out of its history.
+    if args.file.is_none() {
+      let timings_ok = record_timings(&directory, &results, &timings, &args, &mut out)
+          .expect("Can't record timings");
+      // Timings are too noisy to fail a run unless that is asked for.
+      if args.fail_on_regression {
+        passed &= timings_ok;
+      }
+    }
+    writeln!(out).expect("Can't write report");
+    runs.push(InputRun{directory, results, timings, verdicts});
+  }
+  if runs.len() > 1 {
+    compare_inputs(&runs, args.time_factor, &mut out).expect("Can't write report");
+  }
+  write_records(&runs, args.format).expect("Can't write records");
+  if !passed {
+    std::process::exit(1);
+  }
 }
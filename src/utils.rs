@@ -1,6 +1,26 @@
+pub mod answer;
+pub mod cycle;
+pub mod geom;
+pub mod grid;
+pub mod history;
+pub mod intervals;
+pub mod parse;
+pub mod pool;
+pub mod search;
+pub mod select;
+pub mod solution;
+
+pub use answer::Answer;
+pub use geom::{Dir4, Dir8, Point, Point3};
+pub use grid::Grid;
+pub use intervals::IntervalSet;
+pub use parse::{ParseError, blocks, normalize, parse_chars, parse_lines};
+pub use select::pick_days;
+pub use solution::{Day, DayInfo, Example, Solution};
+
 use colored::Colorize;
 use std::cmp::min;
-use std::{fmt, fs};
+use std::{fmt, fs, io};
 use std::path::Path;
 use std::time;
 
@@ -8,7 +28,7 @@ use std::time;
 /// Includes the category, time, and result.
 fn pretty_print(f: &mut fmt::Formatter<'_>, line: &str,
                 duration: time::Duration,
-                output: Option<&str>) -> fmt::Result {
+                output: Option<&Answer>) -> fmt::Result {
     const DISPLAY_WIDTH: usize = 40;
 
     let duration = format!("({:.2?})", duration);
@@ -20,15 +40,17 @@ fn pretty_print(f: &mut fmt::Formatter<'_>, line: &str,
             let dots = DISPLAY_WIDTH - min(DISPLAY_WIDTH - 5, width) - 2;
             write!(f, " {}", ".".repeat(dots).dimmed())?;
 
-            if output.contains('\n') {
-                writeln!(f)?;
+            match output {
+                Answer::Art(art) => {
+                    writeln!(f)?;
 
-                for line in output.trim_matches('\n').lines() {
-                    writeln!(f, "    {}", line.bold())?;
-                }
-                Ok(())
-            } else {
-                writeln!(f, " {}", output.bold())
+                    for line in art.trim_matches('\n').lines() {
+                        writeln!(f, "    {}", line.bold())?;
+                    }
+                    Ok(())
+                },
+                Answer::Unsolved => writeln!(f, " {}", output.to_string().yellow().bold()),
+                _ => writeln!(f, " {}", output.to_string().bold()),
             }
         },
         None => writeln!(f),
@@ -43,49 +65,239 @@ pub fn time<T>(func: &dyn Fn() -> T) -> (time::Duration, T) {
     (start.elapsed(), result)
 }
 
-/// Read the data files from the in_dir into a vector of string.
-pub fn read_inputs(in_dir: &str, days: &[&str], picked: &[bool]) -> Result<Vec<String>, String> {
-  days.iter().zip(picked.iter())
-    .map(|(&day, picked)| {
-      if *picked {
-        let filename = format!("{in_dir}/{day}.txt");
-        fs::read_to_string(Path::new(&filename))
-            .map_err(|e| format!("Error reading {filename}: {}", e))
-      } else {
-        Ok(String::new())
-      }
+/// Read the data file for a single day from the in_dir. The line endings and
+/// whitespace are normalized, so the days don't need to handle files from
+/// other editors.
+pub fn read_input(in_dir: &str, day: &str) -> Result<String, String> {
+  let filename = format!("{in_dir}/{day}.txt");
+  fs::read_to_string(Path::new(&filename))
+      .map(|data| normalize(&data))
+      .map_err(|e| format!("Error reading {filename}: {}", e))
+}
+
+/// Read the data for a day from the file at the path, or from stdin if the
+/// path is -. It is normalized like read_input.
+pub fn read_file(path: &str) -> Result<String, String> {
+  let data = if path == "-" {
+    io::read_to_string(io::stdin()).map_err(|e| format!("Error reading stdin: {e}"))
+  } else {
+    fs::read_to_string(Path::new(path)).map_err(|e| format!("Error reading {path}: {e}"))
+  };
+  data.map(|data| normalize(&data))
+}
+
+/// The phases of running a day's code.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum Phase {
+  Generator,
+  Part1,
+  Part2,
+}
+
+impl Phase {
+  const ALL: [Phase; 3] = [Phase::Generator, Phase::Part1, Phase::Part2];
+
+  /// Convert the number from `phase as usize` back to the phase.
+  pub fn from_index(idx: usize) -> Self {
+    Self::ALL[idx.min(Self::ALL.len() - 1)]
+  }
+}
+
+impl fmt::Display for Phase {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Phase::Generator => "generator",
+      Phase::Part1 => "part 1",
+      Phase::Part2 => "part 2",
+    })
+  }
+}
+
+/// Which parts of a day to run. The generator always runs.
+#[derive(Clone,Copy,Debug,Default,Eq,PartialEq)]
+pub enum Parts {
+  #[default]
+  Both,
+  Part1,
+  Part2,
+}
+
+impl Parts {
+  /// Pick just the given part (1 or 2).
+  pub fn only(part: usize) -> Result<Self, String> {
+    match part {
+      1 => Ok(Parts::Part1),
+      2 => Ok(Parts::Part2),
+      _ => Err(format!("There is no part {part}, only 1 and 2")),
+    }
+  }
+
+  /// Does this include the given part (1 or 2)?
+  pub fn includes(&self, part: usize) -> bool {
+    match self {
+      Parts::Both => part == 1 || part == 2,
+      Parts::Part1 => part == 1,
+      Parts::Part2 => part == 2,
+    }
+  }
+}
+
+/// Why a day didn't produce its answers.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum Failure {
+  /// The day was abandoned in the given phase after the time limit.
+  Timeout(Phase, time::Duration),
+  /// The day panicked in the given phase with the message.
+  Error(Phase, String),
+  /// The generator couldn't parse the input.
+  Parse(ParseError),
+  /// The input couldn't be read, so the day never ran.
+  NoInput(String),
+}
+
+impl Failure {
+  /// Build the failure from the payload of a caught panic.
+  pub fn from_panic(phase: Phase, payload: &(dyn std::any::Any + Send)) -> Self {
+    Failure::Error(phase, pool::panic_message(payload))
+  }
+}
 
-    }).collect::<Result<Vec<String>, String>>()
+impl fmt::Display for Failure {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Failure::Timeout(phase, limit) => write!(f, "TIMEOUT in {phase} after {limit:.2?}"),
+      Failure::Error(phase, message) => write!(f, "ERROR: {message} (in {phase})"),
+      Failure::Parse(err) => write!(f, "PARSE ERROR: {err}"),
+      Failure::NoInput(message) => write!(f, "NO INPUT: {message}"),
+    }
+  }
 }
 
 /// The times and results of running a day's code.
 pub struct DayResult {
     pub day: String,
     pub generate_time: time::Duration,
-    pub part1: (time::Duration, String),
-    pub part2: (time::Duration, String),
+    pub part1: (time::Duration, Answer),
+    pub part2: (time::Duration, Answer),
+    /// The parts that were run. The others are unsolved and take no time.
+    pub parts: Parts,
+    /// Set if the day didn't finish, in which case the answers are unsolved.
+    pub failure: Option<Failure>,
 }
 
 impl DayResult {
+  /// Build the result for a day that didn't finish.
+  pub fn failed(day: &str, failure: Failure) -> Self {
+    DayResult{day: day.to_string(), generate_time: time::Duration::ZERO,
+      part1: (time::Duration::ZERO, Answer::Unsolved),
+      part2: (time::Duration::ZERO, Answer::Unsolved),
+      parts: Parts::Both, failure: Some(failure)}
+  }
+
   /// Return the pretty name for the day
   pub fn pretty_day(&self) -> String {
     self.day.replace("day", "Day ")
   }
 
+  /// The total time for the generator and both parts
+  pub fn total_time(&self) -> time::Duration {
+    self.generate_time + self.part1.0 + self.part2.0
+  }
+
   /// Get the answers without the times
-  pub fn get_answers(&self) -> Vec<String> {
-    vec![self.part1.1.to_string(), self.part2.1.to_string()]
+  pub fn get_answers(&self) -> Vec<Answer> {
+    vec![self.part1.1.clone(), self.part2.1.clone()]
   }
 }
 
 impl fmt::Display for DayResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let duration = format!("({:.2?})", self.generate_time + self.part1.0 + self.part2.0);
+        if let Some(failure) = &self.failure {
+          return writeln!(f, "{} {}", self.pretty_day().bold(), failure.to_string().red().bold())
+        }
+        let duration = format!("({:.2?})", self.total_time());
         writeln!(f, "{} {}", self.pretty_day().bold(), duration.dimmed())?;
         pretty_print(f," · Generator", self.generate_time, None)?;
-        pretty_print(f, " · Part 1", self.part1.0, Some(&self.part1.1))?;
-        pretty_print(f, " · Part 2", self.part2.0, Some(&self.part2.1))
+        if self.parts.includes(1) {
+          pretty_print(f, " · Part 1", self.part1.0, Some(&self.part1.1))?;
+        }
+        if self.parts.includes(2) {
+          pretty_print(f, " · Part 2", self.part2.0, Some(&self.part2.1))?;
+        }
+        Ok(())
+    }
+}
+
+/// Summary statistics for the repeated timings of a single phase.
+#[derive(Clone,Debug,Default,Eq,PartialEq)]
+pub struct TimingStats {
+  pub samples: usize,
+  pub min: time::Duration,
+  pub median: time::Duration,
+  pub mean: time::Duration,
+  pub stddev: time::Duration,
+  pub p95: time::Duration,
+}
+
+impl TimingStats {
+  pub fn from_samples(samples: &[time::Duration]) -> Self {
+    if samples.is_empty() {
+      return Self::default()
     }
+    let mut sorted = samples.to_vec();
+    sorted.sort_unstable();
+    let n = sorted.len();
+    let mean = sorted.iter().map(|d| d.as_secs_f64()).sum::<f64>() / n as f64;
+    let variance = sorted.iter()
+        .map(|d| (d.as_secs_f64() - mean).powi(2))
+        .sum::<f64>() / n as f64;
+    // Use the nearest rank for the percentiles.
+    let rank = |p: f64| sorted[((p * n as f64).ceil() as usize).clamp(1, n) - 1];
+    TimingStats{samples: n, min: sorted[0], median: rank(0.5),
+      mean: time::Duration::from_secs_f64(mean),
+      stddev: time::Duration::from_secs_f64(variance.sqrt()),
+      p95: rank(0.95)}
+  }
+}
+
+impl fmt::Display for TimingStats {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "min {:.2?}  median {:.2?}  mean {:.2?}  stddev {:.2?}  p95 {:.2?}",
+           self.min, self.median, self.mean, self.stddev, self.p95)
+  }
+}
+
+/// The distribution of times from running a day's code repeatedly.
+#[derive(Clone,Debug)]
+pub struct DayTimings {
+  pub day: String,
+  pub generate: TimingStats,
+  pub part1: TimingStats,
+  pub part2: TimingStats,
+  pub total: TimingStats,
+}
+
+impl DayTimings {
+  pub fn from_results(day: &str, results: &[DayResult]) -> Self {
+    let stats = |phase: &dyn Fn(&DayResult) -> time::Duration|
+        TimingStats::from_samples(&results.iter().map(phase).collect::<Vec<_>>());
+    DayTimings{day: day.to_string(),
+      generate: stats(&|r| r.generate_time),
+      part1: stats(&|r| r.part1.0),
+      part2: stats(&|r| r.part2.0),
+      total: stats(&|r| r.total_time())}
+  }
+}
+
+impl fmt::Display for DayTimings {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "{} {}", self.day.replace("day", "Day ").bold(),
+             format!("({} runs)", self.total.samples).dimmed())?;
+    writeln!(f, " · Generator {}", self.generate)?;
+    writeln!(f, " · Part 1    {}", self.part1)?;
+    writeln!(f, " · Part 2    {}", self.part2)?;
+    writeln!(f, " · Total     {}", self.total)
+  }
 }
 
 #[macro_export]
@@ -94,20 +306,11 @@ macro_rules! day_list_internal {
         // Each day's code should be in src/day?.rs.
         $(pub mod $day;)*
 
-        /// Build a lambda to run each day's code
-        pub const FUNCS : &[&dyn Fn(&str) -> $crate::utils::DayResult] = &[
-            $(&|data| {
-                let (generate_time, input) = $crate::utils::time(&|| $day::generator(data));
-                let part1 = $crate::utils::time(&|| $day::part1(&input));
-                let part2 = $crate::utils::time(&|| $day::part2(&input));
-                $crate::utils::DayResult{day: stringify!($day).to_string(),
-                          generate_time,
-                          part1: (part1.0, part1.1.to_string()),
-                          part2: (part2.0, part2.1.to_string())}},)*
+        /// The registry of the implemented days, in order. Each day's
+        /// module defines a Solver that implements Solution.
+        pub const DAYS: &[$crate::utils::Day] = &[
+            $($crate::utils::Day::of::<$day::Solver>(stringify!($day)),)*
         ];
-
-        /// Define the list of implemented day names.
-        pub const NAMES: &[&str] = &[$(stringify!($day)),*];
     }
 }
 
@@ -118,5 +321,88 @@ macro_rules! day_list {
   }
 }
 
+/// Implement Solution for a day as a Solver, using the day's generator,
+/// part1 and part2 functions and its INFO, where the argument is the type
+/// that the generator returns.
+#[macro_export]
+macro_rules! solution {
+  ( $input:ty ) => {
+    pub struct Solver;
+
+    impl $crate::utils::Solution for Solver {
+      type Input = $input;
+
+      const INFO: $crate::utils::DayInfo = INFO;
+
+      fn parse(input: &str) -> Result<$input, $crate::utils::ParseError> {
+        generator(input)
+      }
+
+      fn part1(input: &$input) -> impl Into<$crate::utils::Answer> {
+        part1(input)
+      }
+
+      fn part2(input: &$input) -> impl Into<$crate::utils::Answer> {
+        part2(input)
+      }
+    }
+  };
+}
+
 pub use day_list_internal;
-pub use day_list;
\ No newline at end of file
+pub use day_list;
+#[cfg(test)]
+mod tests {
+  use std::fs;
+  use std::time::Duration;
+  use crate::DAYS;
+  use crate::utils::{read_file, read_input, Answer, Parts, TimingStats};
+
+  #[test]
+  fn test_timing_stats() {
+    let samples: Vec<Duration> = [5, 1, 4, 2, 3].iter()
+        .map(|&ms| Duration::from_millis(ms)).collect();
+    let stats = TimingStats::from_samples(&samples);
+    assert_eq!(5, stats.samples);
+    assert_eq!(Duration::from_millis(1), stats.min);
+    assert_eq!(Duration::from_millis(3), stats.median);
+    assert_eq!(Duration::from_millis(3), stats.mean);
+    assert_eq!(Duration::from_millis(5), stats.p95);
+    assert_eq!(1414, stats.stddev.as_micros());
+    assert_eq!(TimingStats::default(), TimingStats::from_samples(&[]));
+  }
+  /// Raw files with a byte order mark and \r\n line endings should give the
+  /// example answers when read and run the way that the runner, the answers
+  /// test, and the benchmark do.
+  #[test]
+  fn test_read_mangled_input() {
+    let directory = std::env::temp_dir().join(format!("aoc-mangled-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let dir_name = directory.to_string_lossy();
+    for day in DAYS {
+      for example in day.info.examples {
+        // Some examples only fit one of the parts.
+        let parts = match (example.part1, example.part2) {
+          (Some(_), Some(_)) => Parts::Both,
+          (Some(_), None) => Parts::Part1,
+          (None, Some(_)) => Parts::Part2,
+          (None, None) => continue,
+        };
+        let path = directory.join(format!("{}.txt", day.name));
+        fs::write(&path, format!("\u{feff}{}\r\n", example.input.replace('\n', "\r\n")))
+            .unwrap();
+        for input in [read_input(&dir_name, day.name), read_file(&path.to_string_lossy())] {
+          let result = day.run(&input.unwrap(), parts, &|_| {});
+          assert!(result.failure.is_none(), "{}: {:?}", day.name, result.failure);
+          for (expected, answer) in [example.part1, example.part2].iter()
+              .zip(result.get_answers()) {
+            if let Some(expected) = expected {
+              assert_eq!(Answer::from(*expected), answer, "{}", day.name);
+            }
+          }
+        }
+      }
+    }
+    fs::remove_dir_all(&directory).unwrap();
+  }
+}
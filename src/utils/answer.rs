@@ -0,0 +1,167 @@
+use std::fmt;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::{self, Visitor};
+
+/// The answer to one part of a day.
+///
+/// In answers.yml the integers are written as numbers, the text as strings,
+/// and the art as block strings. Unsolved parts are written as null, which
+/// also marks a part that was never run.
+#[derive(Clone,Debug,Default,Eq,Hash,PartialEq)]
+pub enum Answer {
+  Integer(i128),
+  /// A single line of text.
+  Text(String),
+  /// Several lines of text, such as letters drawn with # and . that have to
+  /// be read by eye.
+  Art(String),
+  /// The part isn't solved yet, or has no puzzle, like the second part of
+  /// day 25. It is reported, but never recorded as an answer.
+  #[default]
+  Unsolved,
+}
+
+impl Answer {
+  pub fn is_solved(&self) -> bool {
+    *self != Answer::Unsolved
+  }
+}
+
+macro_rules! answer_from_integer {
+  ($($kind: ty),*) => {
+    $(
+      impl From<$kind> for Answer {
+        fn from(value: $kind) -> Self {
+          Answer::Integer(value as i128)
+        }
+      }
+    )*
+  };
+}
+
+answer_from_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl Answer {
+  /// Text with several lines is Art and anything else is Text.
+  fn from_text(text: &str) -> Self {
+    if text.trim_matches('\n').contains('\n') {
+      Answer::Art(text.to_string())
+    } else {
+      Answer::Text(text.to_string())
+    }
+  }
+}
+
+/// Text that is a number is an Integer and text with several lines is Art.
+impl From<&str> for Answer {
+  fn from(text: &str) -> Self {
+    match text.parse() {
+      Ok(value) => Answer::Integer(value),
+      Err(_) => Answer::from_text(text),
+    }
+  }
+}
+
+impl From<String> for Answer {
+  fn from(text: String) -> Self {
+    Answer::from(text.as_str())
+  }
+}
+
+impl fmt::Display for Answer {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Answer::Integer(value) => write!(f, "{value}"),
+      Answer::Text(text) | Answer::Art(text) => write!(f, "{text}"),
+      Answer::Unsolved => write!(f, "unsolved"),
+    }
+  }
+}
+
+impl Serialize for Answer {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      Answer::Integer(value) => serializer.serialize_i128(*value),
+      Answer::Text(text) | Answer::Art(text) => serializer.serialize_str(text),
+      Answer::Unsolved => serializer.serialize_none(),
+    }
+  }
+}
+
+/// Reads the forms an answer may have in answers.yml.
+struct AnswerVisitor;
+
+impl<'de> Visitor<'de> for AnswerVisitor {
+  type Value = Answer;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "an integer, a string, or null")
+  }
+
+  fn visit_i64<E: de::Error>(self, value: i64) -> Result<Answer, E> {
+    Ok(Answer::Integer(value as i128))
+  }
+
+  fn visit_u64<E: de::Error>(self, value: u64) -> Result<Answer, E> {
+    Ok(Answer::Integer(value as i128))
+  }
+
+  fn visit_i128<E: de::Error>(self, value: i128) -> Result<Answer, E> {
+    Ok(Answer::Integer(value))
+  }
+
+  /// Older files have the numbers as strings and use "" for a part that was
+  /// never run. Only a number written the way it would be printed is taken
+  /// as an Integer, so that text like "007" stays text.
+  fn visit_str<E: de::Error>(self, text: &str) -> Result<Answer, E> {
+    Ok(match text.parse::<i128>() {
+      _ if text.is_empty() => Answer::Unsolved,
+      Ok(value) if value.to_string() == text => Answer::Integer(value),
+      _ => Answer::from_text(text),
+    })
+  }
+
+  fn visit_none<E: de::Error>(self) -> Result<Answer, E> {
+    Ok(Answer::Unsolved)
+  }
+
+  fn visit_unit<E: de::Error>(self) -> Result<Answer, E> {
+    Ok(Answer::Unsolved)
+  }
+}
+
+impl<'de> Deserialize<'de> for Answer {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_any(AnswerVisitor)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::Answer;
+
+  #[test]
+  fn test_from() {
+    assert_eq!(Answer::Integer(-12), Answer::from(-12i64));
+    assert_eq!(Answer::Integer(42), Answer::from("42"));
+    assert_eq!(Answer::Text(String::from("abc")), Answer::from("abc"));
+    assert_eq!(Answer::Art(String::from("#.\n.#\n")), Answer::from("#.\n.#\n"));
+    assert_eq!("unsolved", Answer::Unsolved.to_string());
+  }
+
+  #[test]
+  fn test_yaml() {
+    let answers = vec![Answer::Integer(54667), Answer::Text(String::from("123a")),
+                       Answer::Art(String::from("#..#\n####\n")), Answer::Unsolved];
+    let text = serde_yaml::to_string(&answers).unwrap();
+    assert_eq!("- 54667\n- 123a\n- |\n  #..#\n  ####\n- null\n", text);
+    assert_eq!(answers, serde_yaml::from_str::<Vec<Answer>>(&text).unwrap());
+    let old = "- '54667'\n- ''\n";
+    assert_eq!(vec![Answer::Integer(54667), Answer::Unsolved],
+               serde_yaml::from_str::<Vec<Answer>>(old).unwrap());
+    // Text that only looks like a number has to come back as text.
+    let padded = vec![Answer::Text(String::from("007")), Answer::Text(String::from("+5"))];
+    let text = serde_yaml::to_string(&padded).unwrap();
+    assert_eq!(padded, serde_yaml::from_str::<Vec<Answer>>(&text).unwrap());
+  }
+}
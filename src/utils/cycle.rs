@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Where a sequence of states starts to repeat. The states from step
+/// `prefix` on repeat every `period` steps.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub struct Cycle {
+  pub prefix: usize,
+  pub period: usize,
+}
+
+impl Cycle {
+  /// The earliest step that has the same state as step n.
+  pub fn equivalent_step(&self, n: usize) -> usize {
+    if n < self.prefix {
+      n
+    } else {
+      self.prefix + (n - self.prefix) % self.period
+    }
+  }
+}
+
+/// How the hash based detection remembers the states that it has seen.
+#[derive(Clone,Copy,Debug,Eq,PartialEq)]
+pub enum Memory {
+  /// Keep a copy of each state, so a repeat is never mistaken.
+  States,
+  /// Keep only a 64 bit hash of each state. This uses far less memory for
+  /// large states, but two different states with the same hash would be
+  /// taken as a repeat.
+  Fingerprints,
+}
+
+fn fingerprint<S: Hash>(state: &S) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  state.hash(&mut hasher);
+  hasher.finish()
+}
+
+/// Step the state until it repeats or the limit is reached. If it repeats,
+/// the state is left at step prefix + period.
+fn run<S, K: Eq + Hash>(state: &mut S, step: &mut impl FnMut(&mut S), key: impl Fn(&S) -> K,
+                        limit: usize) -> Option<Cycle> {
+  let mut seen = HashMap::new();
+  for time in 0..limit {
+    if let Some(prefix) = seen.insert(key(state), time) {
+      return Some(Cycle{prefix, period: time - prefix})
+    }
+    step(state);
+  }
+  None
+}
+
+fn run_with<S: Clone + Eq + Hash>(state: &mut S, step: &mut impl FnMut(&mut S), memory: Memory,
+                                  limit: usize) -> Option<Cycle> {
+  match memory {
+    Memory::States => run(state, step, S::clone, limit),
+    Memory::Fingerprints => run(state, step, fingerprint, limit),
+  }
+}
+
+/// Find the cycle by remembering each state until one repeats. The step
+/// function is called once for each step before the repeat, in order, so it
+/// may also record what happens along the way. Never returns if the states
+/// don't repeat.
+pub fn find_cycle<S: Clone + Eq + Hash>(start: S, mut step: impl FnMut(&mut S),
+                                         memory: Memory) -> Cycle {
+  let mut state = start;
+  run_with(&mut state, &mut step, memory, usize::MAX).expect("States never repeat")
+}
+
+/// Find the state after n steps, using the cycle to skip ahead once the
+/// states repeat.
+pub fn nth<S: Clone + Eq + Hash>(start: S, mut step: impl FnMut(&mut S), n: usize,
+                                 memory: Memory) -> S {
+  let mut state = start;
+  if let Some(cycle) = run_with(&mut state, &mut step, memory, n) {
+    // We are at step prefix + period, which matches step prefix.
+    for _ in 0..(n - cycle.prefix) % cycle.period {
+      step(&mut state);
+    }
+  }
+  state
+}
+
+/// Floyd's tortoise and hare, which needs only two copies of the state. The
+/// step function is called on several copies, so it should depend only on
+/// the state.
+pub fn floyd<S: Clone + Eq>(start: S, mut step: impl FnMut(&mut S)) -> Cycle {
+  let mut tortoise = start.clone();
+  let mut hare = start.clone();
+  // Move the hare twice as fast until they meet inside the cycle.
+  loop {
+    step(&mut tortoise);
+    step(&mut hare);
+    step(&mut hare);
+    if tortoise == hare {
+      break
+    }
+  }
+  // They meet again at the start of the cycle if one starts over.
+  let mut prefix = 0;
+  tortoise = start;
+  while tortoise != hare {
+    step(&mut tortoise);
+    step(&mut hare);
+    prefix += 1;
+  }
+  let mut period = 1;
+  step(&mut hare);
+  while tortoise != hare {
+    step(&mut hare);
+    period += 1;
+  }
+  Cycle{prefix, period}
+}
+
+/// Brent's algorithm, which also needs only two copies of the state, but
+/// usually takes fewer steps than Floyd's. As with floyd, the step function
+/// should depend only on the state.
+pub fn brent<S: Clone + Eq>(start: S, mut step: impl FnMut(&mut S)) -> Cycle {
+  // Find the period by moving the tortoise to the hare at each power of two.
+  let mut power = 1;
+  let mut period = 1;
+  let mut tortoise = start.clone();
+  let mut hare = start.clone();
+  step(&mut hare);
+  while tortoise != hare {
+    if power == period {
+      tortoise = hare.clone();
+      power *= 2;
+      period = 0;
+    }
+    step(&mut hare);
+    period += 1;
+  }
+  // Start the hare a period ahead and they meet at the start of the cycle.
+  let mut prefix = 0;
+  tortoise = start.clone();
+  hare = start;
+  for _ in 0..period {
+    step(&mut hare);
+  }
+  while tortoise != hare {
+    step(&mut tortoise);
+    step(&mut hare);
+    prefix += 1;
+  }
+  Cycle{prefix, period}
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::cycle::{brent, find_cycle, floyd, nth, Cycle, Memory};
+
+  /// The states of a small pseudo random generator, which always fall into a
+  /// cycle.
+  fn next(value: &mut u32, modulus: u32) {
+    *value = (*value * *value + 1) % modulus;
+  }
+
+  /// Find the cycle by listing every state.
+  fn brute_force(start: u32, modulus: u32) -> Cycle {
+    let mut states = vec![start];
+    loop {
+      let mut value = *states.last().unwrap();
+      next(&mut value, modulus);
+      if let Some(prefix) = states.iter().position(|&s| s == value) {
+        return Cycle{prefix, period: states.len() - prefix}
+      }
+      states.push(value);
+    }
+  }
+
+  #[test]
+  fn test_detectors() {
+    for modulus in [1, 7, 255, 1009, 4096] {
+      for start in [0, 2, 5, 100] {
+        let step = |v: &mut u32| next(v, modulus);
+        let expected = brute_force(start % modulus, modulus);
+        let start = start % modulus;
+        assert_eq!(expected, floyd(start, step), "{start} % {modulus}");
+        assert_eq!(expected, brent(start, step), "{start} % {modulus}");
+        assert_eq!(expected, find_cycle(start, step, Memory::States));
+        assert_eq!(expected, find_cycle(start, step, Memory::Fingerprints));
+      }
+    }
+  }
+
+  #[test]
+  fn test_nth() {
+    let step = |v: &mut u32| next(v, 1009);
+    let cycle = brute_force(3, 1009);
+    assert!(cycle.prefix > 0 && cycle.period > 1);
+    let mut value = 3;
+    for n in 0..3 * (cycle.prefix + cycle.period) {
+      assert_eq!(value, nth(3, step, n, Memory::States), "step {n}");
+      assert_eq!(value, nth(3, step, n, Memory::Fingerprints), "step {n}");
+      assert_eq!(value, nth(3, step, cycle.equivalent_step(n), Memory::States));
+      step(&mut value);
+    }
+    // The steps along the way are seen once each and in order.
+    let mut seen = Vec::new();
+    let cycle = find_cycle(3, |v| { seen.push(*v); step(v) }, Memory::States);
+    assert_eq!(cycle.prefix + cycle.period, seen.len());
+    assert_eq!(nth(3, step, 1, Memory::States), seen[1]);
+  }
+}
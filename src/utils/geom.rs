@@ -0,0 +1,306 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+use std::str::FromStr;
+use crate::utils::ParseError;
+
+/// The numbers that points can be built from.
+pub trait Coordinate: Copy + Default + Ord + Add<Output = Self> + Sub<Output = Self>
+    + Mul<Output = Self> {}
+
+impl<T> Coordinate for T where T: Copy + Default + Ord + Add<Output = T> + Sub<Output = T>
+    + Mul<Output = T> {}
+
+/// The four directions on a map, where north is up toward smaller y.
+#[derive(Clone,Copy,Debug,Eq,Hash,Ord,PartialEq,PartialOrd)]
+pub enum Dir4 {
+  North,
+  East,
+  South,
+  West,
+}
+
+impl Dir4 {
+  /// The directions in clockwise order.
+  pub const ALL: [Dir4; 4] = [Dir4::North, Dir4::East, Dir4::South, Dir4::West];
+
+  pub fn turn_right(self) -> Self {
+    Self::ALL[(self as usize + 1) % 4]
+  }
+
+  pub fn turn_left(self) -> Self {
+    Self::ALL[(self as usize + 3) % 4]
+  }
+
+  pub fn reverse(self) -> Self {
+    Self::ALL[(self as usize + 2) % 4]
+  }
+}
+
+/// The eight directions on a map, including the diagonals.
+#[derive(Clone,Copy,Debug,Eq,Hash,Ord,PartialEq,PartialOrd)]
+pub enum Dir8 {
+  North,
+  NorthEast,
+  East,
+  SouthEast,
+  South,
+  SouthWest,
+  West,
+  NorthWest,
+}
+
+impl Dir8 {
+  /// The directions in clockwise order.
+  pub const ALL: [Dir8; 8] = [Dir8::North, Dir8::NorthEast, Dir8::East, Dir8::SouthEast,
+    Dir8::South, Dir8::SouthWest, Dir8::West, Dir8::NorthWest];
+
+  /// Turn 45 degrees clockwise.
+  pub fn turn_right(self) -> Self {
+    Self::ALL[(self as usize + 1) % 8]
+  }
+
+  /// Turn 45 degrees counterclockwise.
+  pub fn turn_left(self) -> Self {
+    Self::ALL[(self as usize + 7) % 8]
+  }
+
+  pub fn reverse(self) -> Self {
+    Self::ALL[(self as usize + 4) % 8]
+  }
+
+  /// The signs of the change in x and y for a step in this direction.
+  pub fn delta(self) -> (i8, i8) {
+    match self {
+      Dir8::North => (0, -1),
+      Dir8::NorthEast => (1, -1),
+      Dir8::East => (1, 0),
+      Dir8::SouthEast => (1, 1),
+      Dir8::South => (0, 1),
+      Dir8::SouthWest => (-1, 1),
+      Dir8::West => (-1, 0),
+      Dir8::NorthWest => (-1, -1),
+    }
+  }
+}
+
+impl From<Dir4> for Dir8 {
+  fn from(dir: Dir4) -> Self {
+    Dir8::ALL[dir as usize * 2]
+  }
+}
+
+/// Move the value by the distance in the direction of the sign.
+fn shift<T: Coordinate>(value: T, sign: i8, distance: T) -> T {
+  match sign {
+    1 => value + distance,
+    -1 => value - distance,
+    _ => value,
+  }
+}
+
+/// The distance between two values without going below zero.
+fn difference<T: Coordinate>(left: T, right: T) -> T {
+  if left > right { left - right } else { right - left }
+}
+
+/// A location or offset on a map, where y grows toward the south.
+#[derive(Clone,Copy,Debug,Default,Eq,Hash,Ord,PartialEq,PartialOrd)]
+pub struct Point<T> {
+  pub x: T,
+  pub y: T,
+}
+
+impl<T: Coordinate> Point<T> {
+  pub fn new(x: T, y: T) -> Self {
+    Point{x, y}
+  }
+
+  /// Move the distance in the direction, which may be a Dir4 or a Dir8.
+  pub fn step(self, dir: impl Into<Dir8>, distance: T) -> Self {
+    let (dx, dy) = dir.into().delta();
+    Point{x: shift(self.x, dx, distance), y: shift(self.y, dy, distance)}
+  }
+
+  pub fn manhattan(self, other: Self) -> T {
+    difference(self.x, other.x) + difference(self.y, other.y)
+  }
+}
+
+impl<T: Coordinate> Add for Point<T> {
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self {
+    Point{x: self.x + rhs.x, y: self.y + rhs.y}
+  }
+}
+
+impl<T: Coordinate> AddAssign for Point<T> {
+  fn add_assign(&mut self, rhs: Self) {
+    *self = *self + rhs;
+  }
+}
+
+impl<T: Coordinate> Sub for Point<T> {
+  type Output = Self;
+
+  fn sub(self, rhs: Self) -> Self {
+    Point{x: self.x - rhs.x, y: self.y - rhs.y}
+  }
+}
+
+impl<T: Coordinate> SubAssign for Point<T> {
+  fn sub_assign(&mut self, rhs: Self) {
+    *self = *self - rhs;
+  }
+}
+
+/// Scale the point.
+impl<T: Coordinate> Mul<T> for Point<T> {
+  type Output = Self;
+
+  fn mul(self, rhs: T) -> Self {
+    Point{x: self.x * rhs, y: self.y * rhs}
+  }
+}
+
+impl<T: fmt::Display> fmt::Display for Point<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{},{}", self.x, self.y)
+  }
+}
+
+/// A location or vector in three dimensions.
+#[derive(Clone,Copy,Debug,Default,Eq,Hash,Ord,PartialEq,PartialOrd)]
+pub struct Point3<T> {
+  pub x: T,
+  pub y: T,
+  pub z: T,
+}
+
+impl<T: Coordinate> Point3<T> {
+  pub fn new(x: T, y: T, z: T) -> Self {
+    Point3{x, y, z}
+  }
+
+  pub fn manhattan(self, other: Self) -> T {
+    difference(self.x, other.x) + difference(self.y, other.y) + difference(self.z, other.z)
+  }
+
+  pub fn dot(self, other: Self) -> T {
+    self.x * other.x + self.y * other.y + self.z * other.z
+  }
+
+  pub fn cross(self, other: Self) -> Self {
+    Point3{x: self.y * other.z - self.z * other.y,
+      y: self.z * other.x - self.x * other.z,
+      z: self.x * other.y - self.y * other.x}
+  }
+
+  /// The smallest and largest of each coordinate of the two points.
+  pub fn min(self, other: Self) -> Self {
+    Point3{x: self.x.min(other.x), y: self.y.min(other.y), z: self.z.min(other.z)}
+  }
+
+  pub fn max(self, other: Self) -> Self {
+    Point3{x: self.x.max(other.x), y: self.y.max(other.y), z: self.z.max(other.z)}
+  }
+
+  /// Parse a point written as "x,y,z", where spaces around the numbers are
+  /// ignored.
+  pub fn parse(text: &str) -> Result<Self, ParseError> where T: FromStr {
+    let values = text.split(',')
+        .map(|w| w.trim().parse::<T>().map_err(|_| ParseError::new(w, "Can't parse number")))
+        .collect::<Result<Vec<T>, ParseError>>()?;
+    match values[..] {
+      [x, y, z] => Ok(Point3{x, y, z}),
+      _ => Err(ParseError::new(text, "Need three values")),
+    }
+  }
+}
+
+impl<T> Point3<T> {
+  /// Change each coordinate, such as to a wider type.
+  pub fn map<U>(self, func: impl Fn(T) -> U) -> Point3<U> {
+    Point3{x: func(self.x), y: func(self.y), z: func(self.z)}
+  }
+}
+
+impl<T: Coordinate> Add for Point3<T> {
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self {
+    Point3{x: self.x + rhs.x, y: self.y + rhs.y, z: self.z + rhs.z}
+  }
+}
+
+impl<T: Coordinate> Sub for Point3<T> {
+  type Output = Self;
+
+  fn sub(self, rhs: Self) -> Self {
+    Point3{x: self.x - rhs.x, y: self.y - rhs.y, z: self.z - rhs.z}
+  }
+}
+
+/// Scale the point.
+impl<T: Coordinate> Mul<T> for Point3<T> {
+  type Output = Self;
+
+  fn mul(self, rhs: T) -> Self {
+    Point3{x: self.x * rhs, y: self.y * rhs, z: self.z * rhs}
+  }
+}
+
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{},{},{}", self.x, self.y, self.z)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::geom::{Dir4, Dir8, Point, Point3};
+
+  #[test]
+  fn test_turns() {
+    assert_eq!(Dir4::East, Dir4::North.turn_right());
+    assert_eq!(Dir4::West, Dir4::North.turn_left());
+    assert_eq!(Dir4::North, Dir4::South.reverse());
+    for dir in Dir4::ALL {
+      assert_eq!(dir, dir.turn_left().turn_right());
+      assert_eq!(dir.reverse(), dir.turn_right().turn_right());
+    }
+    assert_eq!(Dir8::NorthWest, Dir8::North.turn_left());
+    assert_eq!(Dir8::SouthWest, Dir8::NorthEast.reverse());
+    assert_eq!(Dir8::West, Dir8::from(Dir4::West));
+  }
+
+  #[test]
+  fn test_point() {
+    let p = Point::new(3, 4);
+    assert_eq!(Point::new(3, -1), p.step(Dir4::North, 5));
+    assert_eq!(Point::new(1, 6), p.step(Dir8::SouthWest, 2));
+    assert_eq!(Point::new(5u32, 4), Point::new(3u32, 4).step(Dir4::East, 2));
+    assert_eq!(Point::new(4, 6), p + Point::new(1, 2));
+    assert_eq!(Point::new(2, 2), p - Point::new(1, 2));
+    assert_eq!(Point::new(6, 8), p * 2);
+    assert_eq!(7, p.manhattan(Point::default()));
+    assert_eq!(4u32, Point::new(1u32, 5).manhattan(Point::new(3, 3)));
+    assert_eq!("3,4", p.to_string());
+  }
+
+  #[test]
+  fn test_point3() {
+    let a = Point3::new(1, 2, 3);
+    let b = Point3::new(-2, 0, 5);
+    assert_eq!(Point3::new(-1, 2, 8), a + b);
+    assert_eq!(Point3::new(3, 2, -2), a - b);
+    assert_eq!(7, a.manhattan(b));
+    assert_eq!(13, a.dot(b));
+    assert_eq!(Point3::new(10, -11, 4), a.cross(b));
+    assert_eq!((Point3::new(-2, 0, 3), Point3::new(1, 2, 5)), (a.min(b), a.max(b)));
+    assert_eq!(Point3::new(1i128, 2, 3), a.map(|v| v as i128));
+    assert_eq!(Ok(Point3::new(19, -13, 30)), Point3::parse("19, -13,  30"));
+    assert!(Point3::<i32>::parse("1,2").is_err());
+    assert!(Point3::<i32>::parse("1,x,2").is_err());
+  }
+}
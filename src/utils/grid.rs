@@ -0,0 +1,255 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use crate::utils::ParseError;
+
+/// A rectangular grid of cells, such as a map drawn with characters. The
+/// cells are stored a row at a time and are indexed by `(x, y)`, where x is
+/// the column and y is the row, counting from the top left.
+#[derive(Clone,Debug,Default,Eq,Hash,PartialEq)]
+pub struct Grid<T> {
+  width: usize,
+  height: usize,
+  cells: Vec<T>,
+}
+
+/// The offsets of the four neighbors that share an edge: north, west, south, and east.
+const EDGE_NEIGHBORS: [(i64, i64); 4] = [(0, -1), (-1, 0), (0, 1), (1, 0)];
+
+/// The offsets of the eight neighbors that share an edge or a corner.
+const ALL_NEIGHBORS: [(i64, i64); 8] =
+    [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+impl<T> Grid<T> {
+  /// Build a grid from its cells a row at a time. The grid can't be empty.
+  pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+    assert!(width > 0 && height > 0, "Grid is {width} by {height}");
+    assert_eq!(width * height, cells.len(), "Grid is {width} by {height}");
+    Grid{width, height, cells}
+  }
+
+  pub fn filled(width: usize, height: usize, value: T) -> Self where T: Clone {
+    Self::new(width, height, vec![value; width * height])
+  }
+
+  /// Parse each line of the text as a row, using the function on each
+  /// character. Errors point at the bad character or the uneven line.
+  pub fn parse_with(input: &str, parse: impl Fn(char) -> Result<T, String>)
+      -> Result<Self, ParseError> {
+    let mut width = None;
+    let mut height = 0;
+    let mut cells = Vec::with_capacity(input.len());
+    for line in input.lines() {
+      let before = cells.len();
+      for (i, ch) in line.char_indices() {
+        cells.push(parse(ch).map_err(|e| ParseError::new(&line[i..i + ch.len_utf8()], e))?);
+      }
+      let found = cells.len() - before;
+      match width {
+        None => width = Some(found),
+        Some(width) if width != found =>
+          return Err(ParseError::new(line, format!("Expected {width} cells, but found {found}"))),
+        _ => {}
+      }
+      height += 1;
+    }
+    match width {
+      Some(width) if width > 0 => Ok(Grid{width, height, cells}),
+      _ => Err(ParseError::new(input, "Empty grid")),
+    }
+  }
+
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  pub fn contains<I: TryInto<usize>>(&self, x: I, y: I) -> bool {
+    self.index_of(x, y).is_some()
+  }
+
+  fn index_of<I: TryInto<usize>>(&self, x: I, y: I) -> Option<usize> {
+    match (x.try_into(), y.try_into()) {
+      (Ok(x), Ok(y)) if x < self.width && y < self.height => Some(y * self.width + x),
+      _ => None,
+    }
+  }
+
+  /// Get the cell if the location is on the grid. Negative locations are
+  /// off the grid.
+  pub fn get<I: TryInto<usize>>(&self, x: I, y: I) -> Option<&T> {
+    self.index_of(x, y).map(|i| &self.cells[i])
+  }
+
+  pub fn get_mut<I: TryInto<usize>>(&mut self, x: I, y: I) -> Option<&mut T> {
+    self.index_of(x, y).map(|i| &mut self.cells[i])
+  }
+
+  /// Get the cell as if the grid was repeated forever in every direction.
+  pub fn get_wrapped<I: Into<i64>>(&self, x: I, y: I) -> &T {
+    let x = x.into().rem_euclid(self.width as i64) as usize;
+    let y = y.into().rem_euclid(self.height as i64) as usize;
+    &self.cells[y * self.width + x]
+  }
+
+  pub fn row(&self, y: usize) -> &[T] {
+    assert!(y < self.height, "Row {y} is off the grid");
+    &self.cells[y * self.width..(y + 1) * self.width]
+  }
+
+  pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+    self.cells.chunks(self.width)
+  }
+
+  pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+    assert!(x < self.width, "Column {x} is off the grid");
+    self.cells[x..].iter().step_by(self.width)
+  }
+
+  pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+    (0..self.width).map(|x| self.column(x))
+  }
+
+  /// Swap the rows and columns.
+  pub fn transpose(&self) -> Self where T: Clone {
+    Grid{width: self.height, height: self.width,
+      cells: self.columns().flatten().cloned().collect()}
+  }
+
+  /// The cells along with their locations, a row at a time.
+  pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+    self.cells.iter().enumerate().map(|(i, cell)| ((i % self.width, i / self.width), cell))
+  }
+
+  /// Find the location of the first cell that matches, a row at a time.
+  pub fn position(&self, pred: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+    self.cells.iter().position(pred).map(|i| (i % self.width, i / self.width))
+  }
+
+  pub fn map<U>(&self, func: impl Fn(&T) -> U) -> Grid<U> {
+    Grid{width: self.width, height: self.height, cells: self.cells.iter().map(func).collect()}
+  }
+
+  fn offsets(&self, x: usize, y: usize, deltas: &'static [(i64, i64)])
+      -> impl Iterator<Item = (usize, usize)> + '_ {
+    deltas.iter()
+        .map(move |(dx, dy)| (x as i64 + dx, y as i64 + dy))
+        .filter(|&(x, y)| self.contains(x, y))
+        .map(|(x, y)| (x as usize, y as usize))
+  }
+
+  /// The locations on the grid that share an edge with the location.
+  pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+    self.offsets(x, y, &EDGE_NEIGHBORS)
+  }
+
+  /// The locations on the grid that share an edge or corner with the location.
+  pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+    self.offsets(x, y, &ALL_NEIGHBORS)
+  }
+}
+
+impl<T: TryFrom<char>> Grid<T> where T::Error: fmt::Display {
+  /// Parse each line of the text as a row of cells.
+  pub fn parse(input: &str) -> Result<Self, ParseError> {
+    Self::parse_with(input, |ch| T::try_from(ch).map_err(|e| e.to_string()))
+  }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+  type Output = T;
+
+  fn index(&self, (x, y): (usize, usize)) -> &T {
+    assert!(x < self.width && y < self.height, "({x}, {y}) is off the grid");
+    &self.cells[y * self.width + x]
+  }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+  fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+    assert!(x < self.width && y < self.height, "({x}, {y}) is off the grid");
+    &mut self.cells[y * self.width + x]
+  }
+}
+
+/// Draw the grid with a line per row.
+impl<T: Copy + Into<char>> fmt::Display for Grid<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for row in self.rows() {
+      writeln!(f, "{}", row.iter().map(|&cell| cell.into()).collect::<String>())?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::grid::Grid;
+
+  #[test]
+  fn test_parse() {
+    let grid = Grid::<char>::parse("ab\ncd\nef\n").unwrap();
+    assert_eq!((2, 3), (grid.width(), grid.height()));
+    assert_eq!('d', grid[(1, 1)]);
+    assert_eq!("ab\ncd\nef\n", grid.to_string());
+    let input = "12\n3\n";
+    let err = Grid::<char>::parse(input).unwrap_err().locate(1, input);
+    assert_eq!((2, "Expected 2 cells, but found 1"), (err.line, err.message.as_str()));
+    let input = "12\n3x\n";
+    let err = Grid::parse_with(input, |ch| ch.to_digit(10).ok_or_else(|| format!("Bad {ch}")))
+        .unwrap_err().locate(1, input);
+    assert_eq!((2, 2), (err.line, err.column));
+    assert!(Grid::<char>::parse("").is_err());
+  }
+
+  #[test]
+  fn test_access() {
+    let mut grid = Grid::<char>::parse("abc\ndef\n").unwrap();
+    assert_eq!(Some(&'f'), grid.get(2, 1));
+    assert_eq!(None, grid.get(3, 1));
+    assert_eq!(None, grid.get(-1i32, 0));
+    assert_eq!('c', *grid.get_wrapped(-1, 2));
+    assert_eq!('d', *grid.get_wrapped(3, -1));
+    *grid.get_mut(0, 0).unwrap() = 'z';
+    grid[(1, 0)] = 'y';
+    assert_eq!("zyc", grid.row(0).iter().collect::<String>());
+    assert_eq!(Some((2, 1)), grid.position(|&c| c == 'f'));
+  }
+
+  #[test]
+  fn test_views() {
+    let grid = Grid::<char>::parse("abc\ndef\n").unwrap();
+    assert_eq!(vec!["abc", "def"],
+               grid.rows().map(|r| r.iter().collect::<String>()).collect::<Vec<_>>());
+    assert_eq!(vec!["ad", "be", "cf"],
+               grid.columns().map(|c| c.collect::<String>()).collect::<Vec<_>>());
+    assert_eq!("ad\nbe\ncf\n", grid.transpose().to_string());
+    assert_eq!(grid, grid.transpose().transpose());
+    assert_eq!("ABC\nDEF\n", grid.map(|c| c.to_ascii_uppercase()).to_string());
+    assert_eq!(((1, 1), &'e'), grid.iter().nth(4).unwrap());
+  }
+
+  #[test]
+  #[should_panic(expected = "Column 3 is off the grid")]
+  fn test_column_off_grid() {
+    let grid = Grid::<char>::parse("abc\ndef\n").unwrap();
+    let _ = grid.column(3);
+  }
+
+  #[test]
+  #[should_panic(expected = "Grid is 0 by 3")]
+  fn test_empty_grid() {
+    Grid::filled(0, 3, '.');
+  }
+
+  #[test]
+  fn test_neighbors() {
+    let grid = Grid::filled(3, 3, '.');
+    assert_eq!(vec![(1, 0), (0, 1), (1, 2), (2, 1)], grid.neighbors4(1, 1).collect::<Vec<_>>());
+    assert_eq!(vec![(0, 1), (1, 0)], grid.neighbors4(0, 0).collect::<Vec<_>>());
+    assert_eq!(8, grid.neighbors8(1, 1).count());
+    assert_eq!(vec![(1, 1), (2, 1), (1, 2)], grid.neighbors8(2, 2).collect::<Vec<_>>());
+  }
+}
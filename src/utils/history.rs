@@ -0,0 +1,159 @@
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::time;
+use serde::{Deserialize, Serialize};
+use crate::utils::DayTimings;
+
+/// Phases that are faster than this are too noisy to judge.
+const MINIMUM_DURATION: time::Duration = time::Duration::from_micros(100);
+
+/// How many previous runs are needed before we look for regressions.
+const MINIMUM_HISTORY: usize = 3;
+
+/// The timings from one run of a day along with information about how it was run.
+#[derive(Clone,Debug,Deserialize,Serialize)]
+pub struct TimingEntry {
+  pub timestamp: String,
+  pub day: String,
+  pub generate_ns: u64,
+  pub part1_ns: u64,
+  pub part2_ns: u64,
+  pub samples: usize,
+  pub profile: String,
+  pub version: String,
+  pub os: String,
+  pub arch: String,
+}
+
+impl TimingEntry {
+  /// Build an entry from the median times of the day.
+  pub fn new(timings: &DayTimings, timestamp: &chrono::DateTime<chrono::Local>) -> Self {
+    TimingEntry{timestamp: timestamp.to_rfc3339(),
+      day: timings.day.clone(),
+      generate_ns: timings.generate.median.as_nanos() as u64,
+      part1_ns: timings.part1.median.as_nanos() as u64,
+      part2_ns: timings.part2.median.as_nanos() as u64,
+      samples: timings.total.samples,
+      profile: (if cfg!(debug_assertions) { "debug" } else { "release" }).to_string(),
+      version: env!("CARGO_PKG_VERSION").to_string(),
+      os: std::env::consts::OS.to_string(),
+      arch: std::env::consts::ARCH.to_string()}
+  }
+
+  fn phases(&self) -> [(&'static str, u64); 3] {
+    [("Generator", self.generate_ns), ("Part 1", self.part1_ns), ("Part 2", self.part2_ns)]
+  }
+
+  /// Were the two runs done in a comparable way?
+  fn is_comparable(&self, other: &TimingEntry) -> bool {
+    self.day == other.day && self.profile == other.profile &&
+        self.os == other.os && self.arch == other.arch
+  }
+}
+
+/// A phase that is slower than it has been recently.
+#[derive(Clone,Debug)]
+pub struct Regression {
+  pub day: String,
+  pub phase: &'static str,
+  pub time: time::Duration,
+  pub median: time::Duration,
+}
+
+impl fmt::Display for Regression {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} {} took {:.2?}, which is {:.0}% slower than the median of {:.2?}",
+           self.day.replace("day", "Day "), self.phase, self.time,
+           (self.time.as_secs_f64() / self.median.as_secs_f64() - 1.0) * 100.0,
+           self.median)
+  }
+}
+
+/// The history of the timings for an input directory, which is stored as one
+/// json record per line in timings.jsonl next to answers.yml.
+#[derive(Clone,Debug,Default)]
+pub struct TimingHistory {
+  entries: Vec<TimingEntry>,
+}
+
+impl TimingHistory {
+  fn make_filename(directory: &str) -> String {
+    Path::new(directory).join("timings.jsonl").to_string_lossy().to_string()
+  }
+
+  pub fn read(directory: &str) -> Result<Self, String> {
+    let filename = Self::make_filename(directory);
+    let Ok(f) = File::open(&filename) else { return Ok(Self::default()) };
+    let entries = BufReader::new(f).lines().enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |l| !l.trim().is_empty()))
+        .map(|(i, line)| {
+          let line = line.map_err(|e| format!("Error reading {filename}: {e}"))?;
+          serde_json::from_str(&line)
+              .map_err(|e| format!("Error parsing {filename} line {}: {e}", i + 1))
+        })
+        .collect::<Result<Vec<TimingEntry>, String>>()?;
+    Ok(TimingHistory{entries})
+  }
+
+  /// Add the new entries to the end of the history file.
+  pub fn append(directory: &str, entries: &[TimingEntry]) -> io::Result<()> {
+    let mut f = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(Self::make_filename(directory))?;
+    for entry in entries {
+      writeln!(f, "{}", serde_json::to_string(entry)?)?;
+    }
+    Ok(())
+  }
+
+  /// Find the phases of the entry that are more than threshold percent slower
+  /// than the median of the last window comparable runs.
+  pub fn find_regressions(&self, entry: &TimingEntry, window: usize,
+                          threshold: f64) -> Vec<Regression> {
+    let previous: Vec<&TimingEntry> = self.entries.iter().rev()
+        .filter(|e| e.is_comparable(entry))
+        .take(window)
+        .collect();
+    if previous.len() < MINIMUM_HISTORY {
+      return Vec::new()
+    }
+    let mut result = Vec::new();
+    for (phase, (name, current)) in entry.phases().iter().enumerate() {
+      let mut history: Vec<u64> = previous.iter().map(|e| e.phases()[phase].1).collect();
+      history.sort_unstable();
+      let median = time::Duration::from_nanos(history[(history.len() - 1) / 2]);
+      let time = time::Duration::from_nanos(*current);
+      if time >= MINIMUM_DURATION &&
+          time.as_secs_f64() > median.as_secs_f64() * (1.0 + threshold / 100.0) {
+        result.push(Regression{day: entry.day.clone(), phase: name, time, median});
+      }
+    }
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::history::{TimingEntry, TimingHistory};
+
+  fn entry(part2_ns: u64) -> TimingEntry {
+    TimingEntry{timestamp: String::new(), day: "day1".to_string(), generate_ns: 10,
+      part1_ns: 1_000_000, part2_ns, samples: 1, profile: "release".to_string(),
+      version: String::new(), os: String::new(), arch: String::new()}
+  }
+
+  #[test]
+  fn test_regressions() {
+    let mut history = TimingHistory::default();
+    assert!(history.find_regressions(&entry(9_000_000), 10, 25.0).is_empty());
+    history.entries = vec![entry(1_000_000), entry(1_100_000), entry(900_000)];
+    assert!(history.find_regressions(&entry(1_200_000), 10, 25.0).is_empty());
+    let found = history.find_regressions(&entry(1_300_000), 10, 25.0);
+    assert_eq!(1, found.len());
+    assert_eq!("Part 2", found[0].phase);
+    assert_eq!(1_000_000, found[0].median.as_nanos());
+  }
+}
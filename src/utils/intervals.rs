@@ -0,0 +1,225 @@
+use std::ops::{Add, Range, Sub};
+
+/// A set of values stored as sorted ranges, which never overlap or touch and
+/// are never empty. Two sets with the same values are always equal.
+#[derive(Clone,Debug,Default,Eq,Hash,PartialEq)]
+pub struct IntervalSet<T> {
+  ranges: Vec<Range<T>>,
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+  pub fn new() -> Self {
+    IntervalSet{ranges: Vec::new()}
+  }
+
+  /// Sort the ranges and merge the ones that overlap or touch.
+  fn normalize(mut ranges: Vec<Range<T>>) -> Self {
+    ranges.retain(|r| r.start < r.end);
+    ranges.sort_unstable_by_key(|r| r.start);
+    let mut result: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+      match result.last_mut() {
+        Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+        _ => result.push(range),
+      }
+    }
+    IntervalSet{ranges: result}
+  }
+
+  pub fn ranges(&self) -> &[Range<T>] {
+    &self.ranges
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.ranges.is_empty()
+  }
+
+  pub fn contains(&self, value: &T) -> bool {
+    // The first range that ends after the value is the only one that could hold it.
+    let idx = self.ranges.partition_point(|r| r.end <= *value);
+    self.ranges.get(idx).is_some_and(|r| r.start <= *value)
+  }
+
+  /// The smallest value in the set.
+  pub fn min(&self) -> Option<T> {
+    self.ranges.first().map(|r| r.start)
+  }
+
+  pub fn insert(&mut self, range: Range<T>) {
+    let mut ranges = std::mem::take(&mut self.ranges);
+    ranges.push(range);
+    *self = Self::normalize(ranges);
+  }
+
+  pub fn union(&self, other: &Self) -> Self {
+    Self::normalize(self.ranges.iter().chain(other.ranges.iter()).cloned().collect())
+  }
+
+  pub fn intersection(&self, other: &Self) -> Self {
+    let mut ranges = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < self.ranges.len() && j < other.ranges.len() {
+      let (left, right) = (&self.ranges[i], &other.ranges[j]);
+      let start = left.start.max(right.start);
+      let end = left.end.min(right.end);
+      if start < end {
+        ranges.push(start..end);
+      }
+      // Move past whichever range finishes first.
+      if left.end < right.end { i += 1 } else { j += 1 }
+    }
+    IntervalSet{ranges}
+  }
+
+  /// The values in this set that aren't in the other one.
+  pub fn difference(&self, other: &Self) -> Self {
+    let mut ranges = Vec::new();
+    let mut j = 0;
+    for range in &self.ranges {
+      let mut start = range.start;
+      while j < other.ranges.len() && other.ranges[j].end <= start {
+        j += 1;
+      }
+      // Cut out each of the other's ranges that overlaps this one.
+      let mut k = j;
+      while k < other.ranges.len() && other.ranges[k].start < range.end {
+        if start < other.ranges[k].start {
+          ranges.push(start..other.ranges[k].start);
+        }
+        start = start.max(other.ranges[k].end);
+        k += 1;
+      }
+      if start < range.end {
+        ranges.push(start..range.end);
+      }
+    }
+    IntervalSet{ranges}
+  }
+
+  /// The values in the universe that aren't in this set.
+  pub fn complement(&self, universe: Range<T>) -> Self {
+    IntervalSet::from(universe).difference(self)
+  }
+
+  /// The number of values in the set.
+  pub fn len(&self) -> T where T: Default + Add<Output = T> + Sub<Output = T> {
+    self.ranges.iter().fold(T::default(), |sum, r| sum + (r.end - r.start))
+  }
+
+  /// Move the parts of the set that fall in each piece by the piece's
+  /// offset. The first piece that holds a value wins and values outside of
+  /// every piece stay where they are.
+  pub fn shift_pieces(&self, pieces: impl IntoIterator<Item = (Range<T>, T)>) -> Self
+      where T: Add<Output = T> {
+    let mut remaining = self.clone();
+    let mut moved = Vec::new();
+    for (source, offset) in pieces {
+      let source = IntervalSet::from(source);
+      moved.extend(remaining.intersection(&source).ranges.iter()
+          .map(|r| r.start + offset..r.end + offset));
+      remaining = remaining.difference(&source);
+    }
+    moved.extend(remaining.ranges);
+    Self::normalize(moved)
+  }
+}
+
+impl<T: Copy + Ord> From<Range<T>> for IntervalSet<T> {
+  fn from(range: Range<T>) -> Self {
+    Self::normalize(vec![range])
+  }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for IntervalSet<T> {
+  fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+    Self::normalize(iter.into_iter().collect())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::ops::Range;
+  use proptest::prelude::*;
+  use crate::utils::intervals::IntervalSet;
+
+  #[test]
+  fn test_normalize() {
+    let set = IntervalSet::from_iter([5..8, 1..3, 3..4, 7..10, 12..12]);
+    assert_eq!(&[1..4, 5..10], set.ranges());
+    assert_eq!(8, set.len());
+    assert!(set.contains(&3) && set.contains(&9));
+    assert!(!set.contains(&4) && !set.contains(&10) && !set.contains(&0));
+    assert_eq!(Some(1), set.min());
+    assert!(IntervalSet::from(4..4).is_empty());
+  }
+
+  #[test]
+  fn test_operations() {
+    let left = IntervalSet::from_iter([0..10, 20..30]);
+    let right = IntervalSet::from(5..25);
+    assert_eq!(IntervalSet::from(0..30), left.union(&right));
+    assert_eq!(&[5..10, 20..25], left.intersection(&right).ranges());
+    assert_eq!(&[0..5, 25..30], left.difference(&right).ranges());
+    assert_eq!(&[10..20, 30..40], left.complement(0..40).ranges());
+    let moved = IntervalSet::from(0..10).shift_pieces([(2..4, 100), (3..6, -3)]);
+    assert_eq!(&[0..3, 6..10, 102..104], moved.ranges());
+  }
+
+  /// Values are kept small so that a u128 can hold any set, even after
+  /// shifting.
+  const LIMIT: i32 = 64;
+
+  fn ranges() -> impl Strategy<Value = Vec<Range<i32>>> {
+    prop::collection::vec((0..LIMIT, 0..LIMIT).prop_map(|(a, b)| a.min(b)..a.max(b)), 0..6)
+  }
+
+  fn bits(ranges: &[Range<i32>]) -> u128 {
+    ranges.iter().flat_map(|r| r.clone()).fold(0, |acc, v| acc | 1 << v)
+  }
+
+  fn check(set: &IntervalSet<i32>) {
+    assert!(set.ranges().iter().all(|r| r.start < r.end));
+    assert!(set.ranges().windows(2).all(|w| w[0].end < w[1].start));
+  }
+
+  proptest! {
+    #[test]
+    fn test_against_bits(left in ranges(), right in ranges()) {
+      let (left_set, right_set) = (IntervalSet::from_iter(left.clone()),
+                                   IntervalSet::from_iter(right.clone()));
+      let (left_bits, right_bits) = (bits(&left), bits(&right));
+      check(&left_set);
+      prop_assert_eq!(left_bits, bits(left_set.ranges()));
+      prop_assert_eq!(left_bits.count_ones() as i32, left_set.len());
+      for v in 0..LIMIT {
+        prop_assert_eq!(left_bits & 1 << v != 0, left_set.contains(&v));
+      }
+      let union = left_set.union(&right_set);
+      let intersection = left_set.intersection(&right_set);
+      let difference = left_set.difference(&right_set);
+      let complement = left_set.complement(0..LIMIT);
+      for set in [&union, &intersection, &difference, &complement] {
+        check(set);
+      }
+      prop_assert_eq!(left_bits | right_bits, bits(union.ranges()));
+      prop_assert_eq!(left_bits & right_bits, bits(intersection.ranges()));
+      prop_assert_eq!(left_bits & !right_bits, bits(difference.ranges()));
+      prop_assert_eq!(!left_bits & ((1 << LIMIT) - 1), bits(complement.ranges()));
+    }
+
+    #[test]
+    fn test_shift_against_bits(values in ranges(),
+                               pieces in prop::collection::vec((0..LIMIT, 0..LIMIT, 0..LIMIT), 0..4)) {
+      let pieces: Vec<(Range<i32>, i32)> = pieces.iter()
+          .map(|&(a, b, offset)| (a.min(b)..a.max(b), offset)).collect();
+      let mut expected: u128 = 0;
+      for v in values.iter().flat_map(|r| r.clone()) {
+        let offset = pieces.iter().find(|(src, _)| src.contains(&v)).map_or(0, |p| p.1);
+        expected |= 1 << (v + offset);
+      }
+      let shifted = IntervalSet::from_iter(values).shift_pieces(pieces);
+      check(&shifted);
+      prop_assert_eq!(expected, bits(shifted.ranges()));
+    }
+  }
+}
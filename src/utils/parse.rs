@@ -0,0 +1,203 @@
+use std::fmt;
+use std::ops::Range;
+
+/// A problem with a day's input along with where it was found.
+///
+/// Parsers build the error from the slice of the input that they couldn't
+/// handle. The line and column are filled in by `locate` once the whole
+/// input is available, which the registry does for every day.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub struct ParseError {
+  pub day: u32,
+  /// The line and column of the bad input, counting from 1. They are 0 if
+  /// the bad input couldn't be found.
+  pub line: usize,
+  pub column: usize,
+  /// The whole line of input with the problem.
+  pub text: String,
+  /// How many characters of the line are bad.
+  pub width: usize,
+  pub message: String,
+  /// The addresses of the bad slice until it is located.
+  span: Range<usize>,
+  /// The first line of the bad slice.
+  bad: String,
+}
+
+impl ParseError {
+  pub fn new(bad: &str, message: impl Into<String>) -> Self {
+    let start = bad.as_ptr() as usize;
+    ParseError{day: 0, line: 0, column: 0, text: String::new(), width: 0,
+      message: message.into(), span: start..start + bad.len(),
+      bad: bad.lines().next().unwrap_or("").to_string()}
+  }
+
+  /// Find the bad slice in the input and fill in the location. If the bad
+  /// text was copied out of the input, the location is left unknown rather
+  /// than guessing which copy of the text it was.
+  pub fn locate(mut self, day: u32, input: &str) -> Self {
+    self.day = day;
+    let base = input.as_ptr() as usize;
+    if base <= self.span.start && self.span.end <= base + input.len() {
+      let offset = self.span.start - base;
+      let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+      let line_end = input[offset..].find('\n').map_or(input.len(), |i| offset + i);
+      self.text = input[line_start..line_end].trim_end_matches('\r').to_string();
+      self.line = input[..offset].matches('\n').count() + 1;
+      self.column = input[line_start..offset].chars().count() + 1;
+      let rest = self.text.chars().count() + 1 - self.column;
+      self.width = self.bad.chars().count().clamp(1, rest.max(1));
+    }
+    self
+  }
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.line == 0 {
+      return write!(f, "{}", self.message)
+    }
+    writeln!(f, "{} at line {}, column {}", self.message, self.line, self.column)?;
+    writeln!(f, "    {}", self.text)?;
+    write!(f, "    {}{}", " ".repeat(self.column - 1), "^".repeat(self.width))
+  }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Put the input into the form the days expect: without a byte order mark,
+/// with \n line endings, and without trailing whitespace on each line or
+/// blank lines at the end. The input ends with a newline unless it's empty.
+pub fn normalize(input: &str) -> String {
+  let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+  let mut result = String::with_capacity(input.len());
+  for line in input.lines() {
+    result.push_str(line.trim_end());
+    result.push('\n');
+  }
+  let end = result.trim_end().len();
+  result.truncate(end);
+  if !result.is_empty() {
+    result.push('\n');
+  }
+  result
+}
+
+/// Split the input into the blocks of lines between blank lines. The blank
+/// lines may hold whitespace and the line endings may be \r\n. Each block is
+/// a slice of the input without its trailing newline, so errors can point
+/// into it.
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+  let line_end = |text: &str, start: usize| text[start..].find('\n')
+      .map_or(text.len(), |i| start + i + 1);
+  let mut rest = input;
+  std::iter::from_fn(move || {
+    while !rest.is_empty() && rest[..line_end(rest, 0)].trim().is_empty() {
+      rest = &rest[line_end(rest, 0)..];
+    }
+    if rest.is_empty() {
+      return None
+    }
+    let mut end = 0;
+    while end < rest.len() && !rest[end..line_end(rest, end)].trim().is_empty() {
+      end = line_end(rest, end);
+    }
+    let block = rest[..end].trim_end();
+    rest = &rest[end..];
+    Some(block)
+  })
+}
+
+/// Parse each line of the input, pointing any error at its line.
+pub fn parse_lines<T>(input: &str, parse: impl Fn(&str) -> Result<T, String>)
+    -> Result<Vec<T>, ParseError> {
+  input.lines()
+      .map(|l| parse(l).map_err(|e| ParseError::new(l, e)))
+      .collect()
+}
+
+/// Parse each character of the text, pointing any error at its character.
+/// This is for a single row that isn't part of a grid, like day 12's springs
+/// that share their line with the group sizes; use Grid for whole maps.
+pub fn parse_chars<T>(text: &str, parse: impl Fn(char) -> Result<T, String>)
+    -> Result<Vec<T>, ParseError> {
+  text.char_indices()
+      .map(|(i, ch)| parse(ch).map_err(|e| ParseError::new(&text[i..i + ch.len_utf8()], e)))
+      .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::DAYS;
+  use crate::utils::parse::{ParseError, blocks, normalize, parse_chars, parse_lines};
+
+  #[test]
+  fn test_locate() {
+    let input = "12\n3x4\n56";
+    let err = ParseError::new(&input[4..5], "Bad digit").locate(7, input);
+    assert_eq!((7, 2, 2, 1), (err.day, err.line, err.column, err.width));
+    assert_eq!("3x4", err.text);
+    assert_eq!("Bad digit at line 2, column 2\n    3x4\n     ^", err.to_string());
+    // A copy of the text isn't searched for, since it may be somewhere else.
+    let copy = String::from("4");
+    let err = ParseError::new(&copy, "Too big").locate(7, input);
+    assert_eq!((0, 0, "Too big"), (err.line, err.column, err.to_string().as_str()));
+    // Only the first line of the bad text is kept.
+    let err = ParseError::new(&input[3..], "Bad block").locate(7, input);
+    assert_eq!(("3x4", 3), (err.bad.as_str(), err.width));
+  }
+
+  #[test]
+  fn test_parse_lines() {
+    let input = "1\n2\nthree";
+    let err = parse_lines(input, |l| l.parse::<u32>().map_err(|e| e.to_string()))
+        .unwrap_err().locate(1, input);
+    assert_eq!((3, 1, 5), (err.line, err.column, err.width));
+    let input = "ab\nacb";
+    let err = input.lines()
+        .map(|l| parse_chars(l, |ch| if ch == 'c' { Err(String::from("No c")) } else { Ok(ch) }))
+        .collect::<Result<Vec<_>, ParseError>>()
+        .unwrap_err().locate(1, input);
+    assert_eq!("No c at line 2, column 2\n    acb\n     ^", err.to_string());
+  }
+
+  #[test]
+  fn test_normalize() {
+    assert_eq!("a\n\nb c\n", normalize("\u{feff}a \r\n\t\r\nb c\t\r\n\r\n \n"));
+    assert_eq!("a\nb\n", normalize("a\nb"));
+    assert_eq!("", normalize("\u{feff}\r\n"));
+  }
+
+  #[test]
+  fn test_blocks() {
+    let input = "\na\r\nb\r\n \r\n\r\nc\n\nd \n";
+    assert_eq!(vec!["a\r\nb", "c", "d"], blocks(input).collect::<Vec<&str>>());
+    assert_eq!(vec!["x"], blocks("x").collect::<Vec<&str>>());
+    assert_eq!(0, blocks(" \n\n").count());
+  }
+
+  /// Every example should give the same answers after a Windows editor has
+  /// had its way with it.
+  #[test]
+  fn test_mangled_examples() {
+    for day in DAYS {
+      for example in day.info.examples {
+        let crlf = example.input.replace('\n', "\r\n");
+        let variants = [format!("\u{feff}{}", example.input), crlf.clone(),
+          format!("\u{feff}{crlf}"), example.input.replace('\n', " \t\n") + "\n\n"];
+        for variant in variants {
+          let input = (day.parse)(&normalize(&variant))
+              .unwrap_or_else(|e| panic!("{} on {variant:?}: {e}", day.name));
+          if let Some(expected) = example.part1 {
+            assert_eq!(expected, (day.part1)(&input).to_string(), "{} part 1 on {variant:?}",
+                       day.name);
+          }
+          if let Some(expected) = example.part2 {
+            assert_eq!(expected, (day.part2)(&input).to_string(), "{} part 2 on {variant:?}",
+                       day.name);
+          }
+        }
+      }
+    }
+  }
+}
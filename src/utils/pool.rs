@@ -0,0 +1,211 @@
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time;
+
+/// The stack size for the worker threads, which matches the main thread so
+/// that the recursive solutions behave the same.
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// What happened to a task.
+#[derive(Clone,Debug,Eq,PartialEq)]
+pub enum Outcome<R> {
+  Done(R),
+  /// The task was abandoned at the deadline. The value is the last stage that
+  /// the task reported.
+  TimedOut(usize),
+  /// The task panicked in the stage with the message.
+  Panicked(usize, String),
+}
+
+/// Get the message from the payload of a caught panic.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+  if let Some(s) = payload.downcast_ref::<&str>() {
+    s.to_string()
+  } else if let Some(s) = payload.downcast_ref::<String>() {
+    s.clone()
+  } else {
+    String::from("unknown panic")
+  }
+}
+
+/// Run the task, catching a panic so that it can't take down its worker.
+fn attempt<T, R>(run: &impl Fn(T, &AtomicUsize) -> R, task: T,
+                 stage: &AtomicUsize) -> Outcome<R> {
+  match panic::catch_unwind(panic::AssertUnwindSafe(|| run(task, stage))) {
+    Ok(result) => Outcome::Done(result),
+    Err(payload) => Outcome::Panicked(stage.load(Ordering::Relaxed), panic_message(&*payload)),
+  }
+}
+
+enum Message<R> {
+  Started(usize, Arc<AtomicUsize>),
+  Finished(usize, Outcome<R>),
+}
+
+type Queue<T> = Arc<Mutex<VecDeque<(usize, T)>>>;
+
+/// Start a worker that takes tasks from the queue until it is empty.
+fn start_worker<T, R, F>(worker: usize, queue: &Queue<T>, run: &Arc<F>,
+                         sender: &mpsc::Sender<Message<R>>, core: Option<core_affinity::CoreId>)
+    where T: Send + 'static,
+          R: Send + 'static,
+          F: Fn(T, &AtomicUsize) -> R + Send + Sync + 'static {
+  let queue = queue.clone();
+  let run = run.clone();
+  let sender = sender.clone();
+  thread::Builder::new()
+      .name(format!("worker-{worker}"))
+      .stack_size(STACK_SIZE)
+      .spawn(move || {
+        if let Some(core) = core {
+          core_affinity::set_for_current(core);
+        }
+        loop {
+          let next = queue.lock().unwrap().pop_front();
+          let Some((idx, task)) = next else { break };
+          let stage = Arc::new(AtomicUsize::new(0));
+          if sender.send(Message::Started(idx, stage.clone())).is_err() {
+            break
+          }
+          if sender.send(Message::Finished(idx, attempt(&*run, task, &stage))).is_err() {
+            break
+          }
+        }
+      })
+      .expect("Can't start worker thread");
+}
+
+/// Run each of the tasks on a pool of worker threads, handing the outcomes to
+/// consume along with the task's index in the same order as the tasks. Each
+/// outcome is consumed as soon as it and all of the earlier ones are done.
+///
+/// The tasks may record their progress in the stage that they are given. If
+/// a task runs past the timeout, its thread is abandoned, the task is reported
+/// as timed out in its last stage, and a new worker takes its place. A task
+/// that panics is reported as panicked, so that the others still finish.
+///
+/// With a single job, no pinning, and no timeout, the tasks run on the
+/// current thread. If pin is set, each worker is bound to its own core.
+pub fn run_in_order<T, R, F, C>(tasks: Vec<T>, jobs: usize, pin: bool,
+                                timeout: Option<time::Duration>, run: F, mut consume: C)
+    where T: Send + 'static,
+          R: Send + 'static,
+          F: Fn(T, &AtomicUsize) -> R + Send + Sync + 'static,
+          C: FnMut(usize, Outcome<R>) {
+  if jobs <= 1 && !pin && timeout.is_none() {
+    for (idx, task) in tasks.into_iter().enumerate() {
+      consume(idx, attempt(&run, task, &AtomicUsize::new(0)));
+    }
+    return
+  }
+  let total = tasks.len();
+  let queue: Queue<T> = Arc::new(Mutex::new(tasks.into_iter().enumerate().collect()));
+  let run = Arc::new(run);
+  let cores = if pin { core_affinity::get_core_ids().unwrap_or_default() } else { Vec::new() };
+  let core = |worker: usize| (!cores.is_empty()).then(|| cores[worker % cores.len()]);
+  let (sender, receiver) = mpsc::channel();
+  let mut workers = jobs.max(1).min(total);
+  for worker in 0..workers {
+    start_worker(worker, &queue, &run, &sender, core(worker));
+  }
+  // Only the replacements for abandoned workers need the sender, so without a
+  // timeout the channel disconnects instead of hanging if the workers are gone.
+  let sender = timeout.map(|_| sender);
+  // The tasks that are running along with their deadlines.
+  let mut running: HashMap<usize, (Option<time::Instant>, Arc<AtomicUsize>)> = HashMap::new();
+  // Put the outcomes back in order as they arrive.
+  let mut pending = BTreeMap::new();
+  let mut next = 0;
+  while next < total {
+    let deadline = running.values().filter_map(|(d, _)| *d).min();
+    let message = match deadline {
+      Some(deadline) => receiver.recv_timeout(
+          deadline.saturating_duration_since(time::Instant::now())),
+      None => receiver.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+    };
+    match message {
+      Ok(Message::Started(idx, stage)) => {
+        running.insert(idx, (timeout.map(|t| time::Instant::now() + t), stage));
+      }
+      Ok(Message::Finished(idx, outcome)) => {
+        // Ignore the results from tasks that we already gave up on.
+        if running.remove(&idx).is_some() {
+          pending.insert(idx, outcome);
+        }
+      }
+      Err(mpsc::RecvTimeoutError::Timeout) => {
+        let now = time::Instant::now();
+        let expired: Vec<usize> = running.iter()
+            .filter(|(_, (d, _))| d.is_some_and(|d| d <= now))
+            .map(|(idx, _)| *idx).collect();
+        for idx in expired {
+          let (_, stage) = running.remove(&idx).unwrap();
+          pending.insert(idx, Outcome::TimedOut(stage.load(Ordering::Relaxed)));
+          // Replace the abandoned worker.
+          if let Some(sender) = &sender {
+            start_worker(workers, &queue, &run, sender, core(workers));
+            workers += 1;
+          }
+        }
+      }
+      Err(mpsc::RecvTimeoutError::Disconnected) => break,
+    }
+    while let Some(outcome) = pending.remove(&next) {
+      consume(next, outcome);
+      next += 1;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::atomic::Ordering;
+  use std::time::Duration;
+  use crate::utils::pool::{Outcome, run_in_order};
+
+  #[test]
+  fn test_order() {
+    for (jobs, pin) in [(1, false), (4, false), (3, true)] {
+      let mut result = Vec::new();
+      run_in_order((0..20_u64).collect(), jobs, pin, None, |x, _| {
+        std::thread::sleep(Duration::from_millis((20 - x) % 7));
+        x * x
+      }, |idx, x| result.push((idx, x)));
+      assert_eq!((0..20_u64).map(|x| (x as usize, Outcome::Done(x * x))).collect::<Vec<_>>(),
+                 result);
+    }
+  }
+
+  #[test]
+  fn test_timeout() {
+    let mut result = Vec::new();
+    run_in_order(vec![1, 10_000, 2], 1, false, Some(Duration::from_millis(200)),
+                 |x, stage| {
+                   stage.store(7, Ordering::Relaxed);
+                   std::thread::sleep(Duration::from_millis(x));
+                   x
+                 }, |_, x| result.push(x));
+    assert_eq!(vec![Outcome::Done(1), Outcome::TimedOut(7), Outcome::Done(2)], result);
+  }
+
+  #[test]
+  fn test_panic() {
+    for (jobs, timeout) in [(1, None), (2, None), (1, Some(Duration::from_secs(60)))] {
+      let mut result = Vec::new();
+      run_in_order(vec![1, 0, 2, 0, 3], jobs, false, timeout, |x, stage| {
+        stage.store(3, Ordering::Relaxed);
+        if x == 0 {
+          panic!("zero");
+        }
+        x
+      }, |_, x| result.push(x));
+      let panicked = || Outcome::Panicked(3, String::from("zero"));
+      assert_eq!(vec![Outcome::Done(1), panicked(), Outcome::Done(2), panicked(), Outcome::Done(3)],
+                 result);
+    }
+  }
+}
@@ -0,0 +1,266 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::collections::hash_map::Entry;
+use std::hash::Hash;
+use std::ops::Add;
+use priority_queue::PriorityQueue;
+
+/// The costs that a search can add up.
+pub trait Cost: Copy + Default + Ord + Add<Output = Self> {}
+
+impl<T> Cost for T where T: Copy + Default + Ord + Add<Output = T> {}
+
+/// The states that a search can visit.
+pub trait State: Clone + Eq + Hash {}
+
+impl<T> State for T where T: Clone + Eq + Hash {}
+
+/// How a state was reached.
+#[derive(Clone,Debug)]
+struct Visit<S, C> {
+  cost: C,
+  parent: Option<S>,
+}
+
+/// The goal that a search reached and the cheapest cost to get there.
+#[derive(Clone,Debug)]
+pub struct Found<S, C> {
+  pub cost: C,
+  pub goal: S,
+  visited: HashMap<S, Visit<S, C>>,
+}
+
+impl<S: State, C> Found<S, C> {
+  /// Rebuild the states along the path from the start to the goal.
+  pub fn path(&self) -> Vec<S> {
+    let mut path = vec![self.goal.clone()];
+    while let Some(parent) = &self.visited[path.last().unwrap()].parent {
+      path.push(parent.clone());
+    }
+    path.reverse();
+    path
+  }
+}
+
+/// Find the cheapest path from the start to a state that is a goal, where the
+/// neighbors function gives the next states and the cost to step to each.
+/// The costs may not be negative.
+pub fn dijkstra<S, C, I>(start: S, neighbors: impl Fn(&S) -> I,
+                         is_goal: impl Fn(&S) -> bool) -> Option<Found<S, C>>
+    where S: State, C: Cost, I: IntoIterator<Item = (S, C)> {
+  astar(start, neighbors, |_| C::default(), is_goal)
+}
+
+/// Like dijkstra, but only finds the cost, which saves remembering how each
+/// state was reached.
+pub fn dijkstra_cost<S, C, I>(start: S, neighbors: impl Fn(&S) -> I,
+                              is_goal: impl Fn(&S) -> bool) -> Option<C>
+    where S: State, C: Cost, I: IntoIterator<Item = (S, C)> {
+  astar_cost(start, neighbors, |_| C::default(), is_goal)
+}
+
+/// Dijkstra's search guided by a heuristic that guesses the cost from a
+/// state to the nearest goal. The heuristic must never guess more than the
+/// real cost, or the result may not be the cheapest.
+pub fn astar<S, C, I>(start: S, neighbors: impl Fn(&S) -> I, heuristic: impl Fn(&S) -> C,
+                      is_goal: impl Fn(&S) -> bool) -> Option<Found<S, C>>
+    where S: State, C: Cost, I: IntoIterator<Item = (S, C)> {
+  guided_search::<S, C, I, true>(start, neighbors, heuristic, is_goal)
+}
+
+/// Like astar, but only finds the cost, which saves remembering how each
+/// state was reached.
+pub fn astar_cost<S, C, I>(start: S, neighbors: impl Fn(&S) -> I, heuristic: impl Fn(&S) -> C,
+                           is_goal: impl Fn(&S) -> bool) -> Option<C>
+    where S: State, C: Cost, I: IntoIterator<Item = (S, C)> {
+  guided_search::<S, C, I, false>(start, neighbors, heuristic, is_goal).map(|found| found.cost)
+}
+
+/// The A* search, which only records the parents when PATH is set.
+fn guided_search<S, C, I, const PATH: bool>(start: S, neighbors: impl Fn(&S) -> I,
+                                            heuristic: impl Fn(&S) -> C,
+                                            is_goal: impl Fn(&S) -> bool) -> Option<Found<S, C>>
+    where S: State, C: Cost, I: IntoIterator<Item = (S, C)> {
+  let mut visited = HashMap::new();
+  let mut pending = PriorityQueue::new();
+  visited.insert(start.clone(), Visit{cost: C::default(), parent: None});
+  // The queue is ordered by the guess at the total cost, and also holds the
+  // cost so far so that it doesn't have to be looked up again.
+  pending.push(start.clone(), Reverse((heuristic(&start), C::default())));
+  while let Some((state, Reverse((_, cost)))) = pending.pop() {
+    if is_goal(&state) {
+      return Some(Found{cost, goal: state, visited})
+    }
+    for (next, step) in neighbors(&state) {
+      let next_cost = cost + step;
+      let parent = || if PATH { Some(state.clone()) } else { None };
+      match visited.entry(next.clone()) {
+        Entry::Occupied(entry) if entry.get().cost <= next_cost => continue,
+        Entry::Occupied(mut entry) =>
+          *entry.get_mut() = Visit{cost: next_cost, parent: parent()},
+        Entry::Vacant(entry) => {
+          entry.insert(Visit{cost: next_cost, parent: parent()});
+        }
+      }
+      let guess = next_cost + heuristic(&next);
+      pending.push_increase(next, Reverse((guess, next_cost)));
+    }
+  }
+  None
+}
+
+/// Find the path from the start to a goal with the fewest steps.
+pub fn bfs<S, I>(start: S, neighbors: impl Fn(&S) -> I,
+                 is_goal: impl Fn(&S) -> bool) -> Option<Found<S, usize>>
+    where S: State, I: IntoIterator<Item = S> {
+  let mut visited = HashMap::new();
+  let mut pending = VecDeque::new();
+  visited.insert(start.clone(), Visit{cost: 0, parent: None});
+  pending.push_back(start);
+  while let Some(state) = pending.pop_front() {
+    let cost = visited[&state].cost;
+    if is_goal(&state) {
+      return Some(Found{cost, goal: state, visited})
+    }
+    for next in neighbors(&state) {
+      if let Entry::Vacant(entry) = visited.entry(next.clone()) {
+        entry.insert(Visit{cost: cost + 1, parent: Some(state.clone())});
+        pending.push_back(next);
+      }
+    }
+  }
+  None
+}
+
+/// Find the number of steps to each state that can be reached from the
+/// start in at most the limit.
+pub fn bfs_distances<S, I>(start: S, neighbors: impl Fn(&S) -> I,
+                           limit: usize) -> HashMap<S, usize>
+    where S: State, I: IntoIterator<Item = S> {
+  let mut distances = HashMap::new();
+  let mut frontier = vec![start.clone()];
+  distances.insert(start, 0);
+  for steps in 1..=limit {
+    let mut next_frontier = Vec::new();
+    for state in &frontier {
+      for next in neighbors(state) {
+        if let Entry::Vacant(entry) = distances.entry(next.clone()) {
+          entry.insert(steps);
+          next_frontier.push(next);
+        }
+      }
+    }
+    if next_frontier.is_empty() {
+      break
+    }
+    frontier = next_frontier;
+  }
+  distances
+}
+
+/// Find the cheapest path to a goal when every step costs either zero or one.
+pub fn bfs01<S, C, I>(start: S, neighbors: impl Fn(&S) -> I,
+                      is_goal: impl Fn(&S) -> bool) -> Option<Found<S, C>>
+    where S: State, C: Cost, I: IntoIterator<Item = (S, C)> {
+  let zero = C::default();
+  let mut visited = HashMap::new();
+  let mut pending = VecDeque::new();
+  visited.insert(start.clone(), Visit{cost: zero, parent: None});
+  pending.push_back((start, zero));
+  while let Some((state, cost)) = pending.pop_front() {
+    // Skip the entries that were queued before a cheaper way was found.
+    if visited[&state].cost < cost {
+      continue
+    }
+    if is_goal(&state) {
+      return Some(Found{cost, goal: state, visited})
+    }
+    for (next, step) in neighbors(&state) {
+      let next_cost = cost + step;
+      match visited.entry(next.clone()) {
+        Entry::Occupied(entry) if entry.get().cost <= next_cost => continue,
+        Entry::Occupied(mut entry) =>
+          *entry.get_mut() = Visit{cost: next_cost, parent: Some(state.clone())},
+        Entry::Vacant(entry) => {
+          entry.insert(Visit{cost: next_cost, parent: Some(state.clone())});
+        }
+      }
+      if step == zero {
+        pending.push_front((next, next_cost));
+      } else {
+        pending.push_back((next, next_cost));
+      }
+    }
+  }
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::{Dir4, Grid, Point};
+  use crate::utils::search::{astar, astar_cost, bfs, bfs01, bfs_distances, dijkstra, dijkstra_cost};
+
+  const MAZE: &str =
+"S.#.....
+.#..#.#.
+...##.#.
+.#....#.
+...#...G";
+
+  fn maze() -> (Grid<char>, Point<i32>, Point<i32>) {
+    let grid = Grid::<char>::parse(MAZE).unwrap();
+    let find = |ch| grid.position(|&c| c == ch).map(|(x, y)| Point::new(x as i32, y as i32));
+    let (start, goal) = (find('S').unwrap(), find('G').unwrap());
+    (grid, start, goal)
+  }
+
+  fn open(grid: &Grid<char>, at: &Point<i32>) -> impl Iterator<Item = Point<i32>> {
+    let at = *at;
+    Dir4::ALL.into_iter().map(move |d| at.step(d, 1))
+        .filter(|p| grid.get(p.x, p.y).is_some_and(|&c| c != '#'))
+        .collect::<Vec<_>>().into_iter()
+  }
+
+  #[test]
+  fn test_unit_costs() {
+    let (grid, start, goal) = maze();
+    let found = bfs(start, |p| open(&grid, p), |p| *p == goal).unwrap();
+    assert_eq!(11, found.cost);
+    let path = found.path();
+    assert_eq!((start, goal, 12), (path[0], path[11], path.len()));
+    assert!(path.windows(2).all(|w| w[0].manhattan(w[1]) == 1));
+    let weighted = |p: &Point<i32>| open(&grid, p).map(|n| (n, 1));
+    assert_eq!(11, dijkstra(start, weighted, |p| *p == goal).unwrap().cost);
+    assert_eq!(11, astar(start, weighted, |p| p.manhattan(goal), |p| *p == goal).unwrap().cost);
+    assert_eq!(11, bfs01(start, weighted, |p| *p == goal).unwrap().cost);
+    assert_eq!(Some(11), dijkstra_cost(start, weighted, |p| *p == goal));
+    assert_eq!(Some(11), astar_cost(start, weighted, |p| p.manhattan(goal), |p| *p == goal));
+    assert!(bfs(start, |p| open(&grid, p), |p| p.x > 10).is_none());
+    let distances = bfs_distances(start, |p| open(&grid, p), 3);
+    assert_eq!((6, Some(&3), None), (distances.len(), distances.get(&Point::new(1, 2)),
+                                      distances.get(&Point::new(3, 0))));
+  }
+
+  #[test]
+  fn test_weighted() {
+    // Walking east is free and other steps cost one, so the best route goes
+    // the long way around to use the free steps.
+    let (grid, start, goal) = maze();
+    let costs = |p: &Point<i32>| {
+      let at = *p;
+      open(&grid, p).map(move |n| (n, if n.x > at.x { 0 } else { 1 }))
+    };
+    let found = dijkstra(start, costs, |p| *p == goal).unwrap();
+    assert_eq!(4, found.cost);
+    assert_eq!(4, bfs01(start, costs, |p| *p == goal).unwrap().cost);
+    let path = found.path();
+    let total: i32 = path.windows(2).map(|w| if w[1].x > w[0].x { 0 } else { 1 }).sum();
+    assert_eq!(found.cost, total);
+    // Adding one costs the new number and doubling costs three.
+    let graph = |&n: &u32| [(n + 1, n + 1), (n * 2, 3)].into_iter().filter(|(n, _)| *n <= 20);
+    let found = dijkstra(1, graph, |&n| n == 20).unwrap();
+    assert_eq!((16, vec![1, 2, 4, 5, 10, 20]), (found.cost, found.path()));
+    let found = astar(1, graph, |&n| if n < 20 { 1 } else { 0 }, |&n| n == 20).unwrap();
+    assert_eq!(16, found.cost);
+  }
+}
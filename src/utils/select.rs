@@ -0,0 +1,189 @@
+use std::collections::BTreeSet;
+use crate::DAYS;
+use crate::utils::Parts;
+
+/// Work out which days, and which of their parts, were picked. Each term
+/// may hold a comma separated list of:
+///   * a day, as 12 or day12, or 12.2 for just its second part
+///   * a range of days, as 1-10, 3..7, 15.., or ..5
+///   * all, all-TAG for the days without the tag, or TAG for the days with it
+///   * any of those after a ^ to leave those days out
+///
+/// The terms are applied in order. If none of them add days, the exclusions
+/// are taken from all of the days. The default parts are used for the days
+/// that don't give a part.
+pub fn pick_days(terms: &[String], default: Parts) -> Result<Vec<Option<Parts>>, String> {
+  let terms: Vec<&str> = terms.iter()
+      .flat_map(|t| t.split(','))
+      .map(str::trim)
+      .filter(|t| !t.is_empty())
+      .collect();
+  let mut day_filter = vec![None; DAYS.len()];
+  if terms.iter().all(|t| t.starts_with('^')) {
+    day_filter.fill(Some(default));
+  }
+  for term in terms {
+    let (exclude, term) = match term.strip_prefix('^') {
+      Some(rest) => (true, rest),
+      None => (false, term),
+    };
+    let (picked, part) = parse_term(term)?;
+    if exclude && part.is_some() {
+      return Err(format!("Can't leave out just one part with ^{term}"));
+    }
+    for idx in picked {
+      day_filter[idx] = if exclude { None } else { Some(part.unwrap_or(default)) };
+    }
+  }
+  Ok(day_filter)
+}
+
+/// Find the indexes in DAYS of a single term without its ^, along with the
+/// part if the term gave one.
+fn parse_term(term: &str) -> Result<(Vec<usize>, Option<Parts>), String> {
+  if term == "all" {
+    return Ok(((0..DAYS.len()).collect(), None))
+  }
+  if let Some(tag) = term.strip_prefix("all-") {
+    check_tag(tag)?;
+    return Ok((matching(|idx| !DAYS[idx].info.has_tag(tag)), None))
+  }
+  if let Some((start, end)) = split_range(term) {
+    let start = start.unwrap_or_else(first_day);
+    let end = end.unwrap_or_else(last_day);
+    find_day(start)?;
+    find_day(end)?;
+    if start > end {
+      return Err(format!("The range {term} is backwards, so it has no days."))
+    }
+    return Ok((matching(|idx| (start..=end).contains(&DAYS[idx].info.day)), None))
+  }
+  if let Some((day, part)) = term.split_once('.') {
+    let part = part.parse().map_err(|_| format!("Can't parse part in {term}"))?;
+    return Ok((vec![find_day(day_number(day)?)?], Some(Parts::only(part)?)))
+  }
+  if term.starts_with(|ch: char| ch.is_ascii_digit()) || is_day_name(term) {
+    return Ok((vec![find_day(day_number(term)?)?], None))
+  }
+  check_tag(term)?;
+  Ok((matching(|idx| DAYS[idx].info.has_tag(term)), None))
+}
+
+/// Split a range into its start and end, which are None if they are left
+/// empty. Terms whose sides aren't both day numbers, such as a tag with a
+/// dash in it, aren't ranges.
+fn split_range(term: &str) -> Option<(Option<u32>, Option<u32>)> {
+  let (start, end) = term.split_once("..").or_else(|| term.split_once('-'))?;
+  let side = |text: &str| match text {
+    "" => Some(None),
+    _ => day_number(text).ok().map(Some),
+  };
+  Some((side(start)?, side(end)?))
+}
+
+fn is_day_name(term: &str) -> bool {
+  term.strip_prefix("day").is_some_and(|n| n.parse::<u32>().is_ok())
+}
+
+fn day_number(text: &str) -> Result<u32, String> {
+  text.strip_prefix("day").unwrap_or(text).parse()
+      .map_err(|_| format!("Can't parse day {text}. {}", known_days()))
+}
+
+fn find_day(day: u32) -> Result<usize, String> {
+  DAYS.iter().position(|d| d.info.day == day)
+      .ok_or_else(|| format!("Can't find implementation for day{day}. {}", known_days()))
+}
+
+fn matching(pred: impl Fn(usize) -> bool) -> Vec<usize> {
+  (0..DAYS.len()).filter(|&idx| pred(idx)).collect()
+}
+
+fn first_day() -> u32 {
+  DAYS.iter().map(|d| d.info.day).min().unwrap_or(0)
+}
+
+fn last_day() -> u32 {
+  DAYS.iter().map(|d| d.info.day).max().unwrap_or(0)
+}
+
+fn all_tags() -> BTreeSet<&'static str> {
+  DAYS.iter().flat_map(|d| d.info.tags.iter().copied()).collect()
+}
+
+fn check_tag(tag: &str) -> Result<(), String> {
+  if all_tags().contains(tag) {
+    Ok(())
+  } else {
+    Err(format!("Unknown day or tag {tag}. {}", known_days()))
+  }
+}
+
+/// Describe the implemented days and tags, such as "The implemented days
+/// are 1-25 and the tags are grid, slow."
+fn known_days() -> String {
+  let mut days: Vec<u32> = DAYS.iter().map(|d| d.info.day).collect();
+  days.sort();
+  let mut runs: Vec<(u32, u32)> = Vec::new();
+  for day in days {
+    match runs.last_mut() {
+      Some((_, end)) if *end + 1 == day => *end = day,
+      _ => runs.push((day, day)),
+    }
+  }
+  let runs: Vec<String> = runs.iter()
+      .map(|&(start, end)| if start == end { start.to_string() } else { format!("{start}-{end}") })
+      .collect();
+  let tags: Vec<&str> = all_tags().into_iter().collect();
+  format!("The implemented days are {} and the tags are {}.", runs.join(", "), tags.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::DAYS;
+  use crate::utils::Parts;
+  use crate::utils::select::pick_days;
+
+  /// Pick the days and return their numbers.
+  fn picked(terms: &[&str]) -> Result<Vec<usize>, String> {
+    let terms: Vec<String> = terms.iter().map(|t| t.to_string()).collect();
+    Ok(pick_days(&terms, Parts::Both)?.iter().enumerate()
+        .filter(|(_, p)| p.is_some())
+        .map(|(idx, _)| idx + 1)
+        .collect())
+  }
+
+  #[test]
+  fn test_pick_days() {
+    assert_eq!(Ok((1..=DAYS.len()).collect()), picked(&[]));
+    assert_eq!(Ok(vec![3, 12]), picked(&["12", "day3"]));
+    assert_eq!(Ok(vec![1, 2, 3, 7]), picked(&["1-3,7"]));
+    assert_eq!(Ok(vec![4, 5]), picked(&["4..5"]));
+    assert_eq!(Ok(vec![24, 25]), picked(&["24.."]));
+    assert_eq!(Ok(vec![1, 2]), picked(&["..2"]));
+    assert_eq!(Ok(vec![1, 2, 4, 5]), picked(&["1-5", "^3"]));
+    assert_eq!(Ok((1..=24).collect()), picked(&["^25"]));
+    assert_eq!(Ok((1..=25).filter(|&d| d != 12).collect()), picked(&["all-slow"]));
+    assert_eq!(Ok(vec![8, 20, 23, 25]), picked(&["graph", "^17"]));
+    let terms = vec![String::from("12.2"), String::from("3")];
+    let filter = pick_days(&terms, Parts::Part1).unwrap();
+    assert_eq!((Some(Parts::Part2), Some(Parts::Part1)), (filter[11], filter[2]));
+  }
+
+  #[test]
+  fn test_pick_errors() {
+    let err = picked(&["30"]).unwrap_err();
+    assert_eq!("Can't find implementation for day30. The implemented days are 1-25 \
+                and the tags are graph, grid, slow.", err);
+    assert!(picked(&["20-30"]).unwrap_err().starts_with("Can't find implementation for day30."));
+    assert_eq!(Err(String::from("The range 10-3 is backwards, so it has no days.")),
+               picked(&["10-3"]));
+    assert!(picked(&["5..day4"]).is_err());
+    assert!(picked(&["fast"]).unwrap_err().starts_with("Unknown day or tag fast."));
+    // A dash only makes a range between day numbers, so this is looked up as a tag.
+    assert!(picked(&["two-part"]).unwrap_err().starts_with("Unknown day or tag two-part."));
+    assert!(picked(&["all-fast"]).is_err());
+    assert!(picked(&["12.3"]).is_err());
+    assert!(picked(&["^12.2"]).is_err());
+  }
+}
@@ -0,0 +1,147 @@
+use std::any::Any;
+use std::fmt;
+use std::time::Duration;
+use crate::utils::{Answer, DayResult, Failure, ParseError, Parts, Phase, time};
+
+/// An example from the puzzle text along with the answers that it should give.
+/// The answers are None when the example doesn't apply to that part as written,
+/// such as when the puzzle uses different parameters for the example.
+#[derive(Clone,Copy,Debug)]
+pub struct Example {
+  pub input: &'static str,
+  pub part1: Option<&'static str>,
+  pub part2: Option<&'static str>,
+}
+
+/// The description of a day's puzzle.
+#[derive(Clone,Copy,Debug)]
+pub struct DayInfo {
+  pub title: &'static str,
+  pub year: u32,
+  pub day: u32,
+  pub examples: &'static [Example],
+  /// Labels for grouping the days, such as "slow" for the ones that take
+  /// too long to run as part of the regular tests.
+  pub tags: &'static [&'static str],
+}
+
+impl DayInfo {
+  pub fn has_tag(&self, tag: &str) -> bool {
+    self.tags.contains(&tag)
+  }
+}
+
+/// The code for a single day's puzzle.
+pub trait Solution {
+  /// The parsed form of the input that both parts share.
+  type Input: 'static;
+
+  const INFO: DayInfo;
+
+  fn parse(input: &str) -> Result<Self::Input, ParseError>;
+  fn part1(input: &Self::Input) -> impl Into<Answer>;
+  fn part2(input: &Self::Input) -> impl Into<Answer>;
+}
+
+/// The parsed input for a day, with its type hidden so that every day
+/// can be kept in the same registry.
+pub type ParsedInput = Box<dyn Any>;
+
+fn parse_erased<S: Solution>(input: &str) -> Result<ParsedInput, ParseError> {
+  S::parse(input)
+      .map(|i| Box::new(i) as ParsedInput)
+      .map_err(|e| e.locate(S::INFO.day, input))
+}
+
+fn downcast<S: Solution>(input: &ParsedInput) -> &S::Input {
+  input.downcast_ref().expect("Parsed input is from a different day")
+}
+
+fn part1_erased<S: Solution>(input: &ParsedInput) -> Answer {
+  S::part1(downcast::<S>(input)).into()
+}
+
+fn part2_erased<S: Solution>(input: &ParsedInput) -> Answer {
+  S::part2(downcast::<S>(input)).into()
+}
+
+/// An entry in the registry of days, which is built by `day_list!`.
+#[derive(Clone,Copy)]
+pub struct Day {
+  pub name: &'static str,
+  pub info: DayInfo,
+  pub parse: fn(&str) -> Result<ParsedInput, ParseError>,
+  pub part1: fn(&ParsedInput) -> Answer,
+  pub part2: fn(&ParsedInput) -> Answer,
+}
+
+impl Day {
+  pub const fn of<S: Solution>(name: &'static str) -> Self {
+    Day{name, info: S::INFO, parse: parse_erased::<S>, part1: part1_erased::<S>,
+      part2: part2_erased::<S>}
+  }
+
+  /// Run the generator and the picked parts on the data. The progress
+  /// function is told as each phase starts.
+  pub fn run(&self, data: &str, parts: Parts, progress: &dyn Fn(Phase)) -> DayResult {
+    progress(Phase::Generator);
+    let (generate_time, input) = time(&|| (self.parse)(data));
+    let input = match input {
+      Ok(input) => input,
+      Err(err) => return DayResult::failed(self.name, Failure::Parse(err)),
+    };
+    let run_part = |part: usize, phase: Phase, func: fn(&ParsedInput) -> Answer| {
+      if parts.includes(part) {
+        progress(phase);
+        time(&|| func(&input))
+      } else {
+        (Duration::ZERO, Answer::Unsolved)
+      }
+    };
+    let part1 = run_part(1, Phase::Part1, self.part1);
+    let part2 = run_part(2, Phase::Part2, self.part2);
+    DayResult{day: self.name.to_string(), generate_time, part1, part2, parts, failure: None}
+  }
+}
+
+impl fmt::Debug for Day {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Day").field("name", &self.name).field("info", &self.info).finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::DAYS;
+  use crate::utils::{Answer, Parts};
+
+  #[test]
+  fn test_examples() {
+    for (idx, day) in DAYS.iter().enumerate() {
+      assert_eq!(format!("day{}", day.info.day), day.name);
+      assert_eq!(idx + 1, day.info.day as usize);
+      for example in day.info.examples {
+        let input = (day.parse)(example.input).unwrap();
+        if let Some(expected) = example.part1 {
+          assert_eq!(expected, (day.part1)(&input).to_string(), "{} part 1", day.name);
+        }
+        if let Some(expected) = example.part2 {
+          assert_eq!(expected, (day.part2)(&input).to_string(), "{} part 2", day.name);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_run_parts() {
+    // Day 2's first example has answers for both parts.
+    let day = DAYS.iter().find(|d| d.name == "day2").unwrap();
+    let example = day.info.examples[0];
+    assert!(example.part1.is_some() && example.part2.is_some());
+    let result = day.run(example.input, Parts::Part2, &|_| {});
+    assert_eq!(vec![Answer::Unsolved, Answer::from(example.part2.unwrap())], result.get_answers());
+    let result = day.run(example.input, Parts::Both, &|_| {});
+    assert_eq!(vec![Answer::from(example.part1.unwrap()), Answer::from(example.part2.unwrap())],
+               result.get_answers());
+  }
+}
@@ -0,0 +1,80 @@
+//! Check every registered day against the answers recorded in each input
+//! directory's answers.yml. There is one test per directory, day, and part.
+//! Days without an input file or a recorded answer are ignored, as are the
+//! days tagged slow unless the tests are run with --include-ignored.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::thread;
+use libtest_mimic::{Arguments, Failed, Trial};
+use omalley_aoc2023::{DAYS, utils};
+use serde::Deserialize;
+
+/// Match the runner's main thread, since some of the days recurse deeply.
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+#[derive(Default, Deserialize)]
+struct Answers {
+  days: BTreeMap<String, Vec<utils::Answer>>,
+}
+
+/// Find the input directories, which are the ones with an answers.yml.
+fn input_directories(root: &Path) -> Vec<PathBuf> {
+  let mut result: Vec<PathBuf> = root.read_dir().expect("Can't read crate directory")
+      .filter_map(|entry| entry.ok().map(|e| e.path()))
+      .filter(|path| path.join("answers.yml").is_file())
+      .collect();
+  result.sort();
+  result
+}
+
+/// Parse the input and run the part on a thread with a large enough stack.
+fn check(day: &'static utils::Day, part: usize, input: String, expected: utils::Answer)
+    -> Result<(), Failed> {
+  let answer = thread::Builder::new()
+      .stack_size(STACK_SIZE)
+      .spawn(move || {
+        let parsed = (day.parse)(&input).map_err(|e| e.to_string())?;
+        Ok::<utils::Answer, String>(
+            if part == 1 { (day.part1)(&parsed) } else { (day.part2)(&parsed) })
+      })
+      .expect("Can't start test thread")
+      .join()
+      .map_err(|payload| utils::Failure::from_panic(utils::Phase::from_index(part), &*payload)
+          .to_string())??;
+  if answer == expected {
+    Ok(())
+  } else {
+    Err(format!("expected {expected} but got {answer}").into())
+  }
+}
+
+fn main() {
+  let args = Arguments::from_args();
+  let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+  let mut trials = Vec::new();
+  for directory in input_directories(root) {
+    let name = directory.file_name().unwrap().to_string_lossy().to_string();
+    let answers: Answers = serde_yaml::from_reader(
+        File::open(directory.join("answers.yml")).expect("Can't open answers"))
+        .unwrap_or_else(|e| panic!("Can't read {name}/answers.yml: {e}"));
+    for day in DAYS {
+      let input = utils::read_input(&directory.to_string_lossy(), day.name).ok();
+      for part in 1..=2 {
+        let expected = answers.days.get(day.name).and_then(|a| a.get(part - 1))
+            .filter(|a| a.is_solved()).cloned();
+        let trial_name = format!("{name}::{}::part{part}", day.name);
+        trials.push(match (input.clone(), expected) {
+          (Some(input), Some(expected)) if day.info.has_tag("slow") =>
+            Trial::test(trial_name, move || check(day, part, input, expected))
+                .with_kind("slow").with_ignored_flag(true),
+          (Some(input), Some(expected)) =>
+            Trial::test(trial_name, move || check(day, part, input, expected)),
+          _ => Trial::test(trial_name, || Ok(())).with_kind("missing").with_ignored_flag(true),
+        });
+      }
+    }
+  }
+  libtest_mimic::run(&args, trials).exit();
+}